@@ -1,32 +1,32 @@
-use std::path::PathBuf;
+use common::{
+    aoc,
+    combinators::{self, integer, lines},
+    timing, Answer, AocError, ParseError,
+};
 
 extern crate common;
 
-fn day1_part1(data: &Vec<&str>) -> String {
+fn parse_input(input: &str) -> Result<Vec<u32>, ParseError> {
+    combinators::parse(input, lines(integer::<u32>))
+}
+
+fn day1_part1(data: &[u32]) -> String {
     data.windows(2)
-        .map(|x| {
-            let first = x[0].parse::<u32>().unwrap();
-            let second = x[1].parse::<u32>().unwrap();
-            if second > first {
-                1
-            } else {
-                0
-            }
-        })
+        .map(|x| if x[1] > x[0] { 1 } else { 0 })
         .sum::<u32>()
         .to_string()
 }
 
-pub fn day1_part1_solution(path: &PathBuf) -> String {
-    let data = common::read_file(path);
-    let split: Vec<&str> = data.trim().split("\n").collect();
-    day1_part1(&split)
+#[aoc(year = 2021, day = 1, part = 1)]
+pub fn day1_part1_solution(input: &str) -> Result<Answer, AocError> {
+    let depths = timing::parse(|| parse_input(input))?;
+    Ok(day1_part1(&depths))
 }
 
-fn day1_part2(data: &Vec<&str>) -> String {
+fn day1_part2(data: &[u32]) -> String {
     let parsed = data
         .windows(3)
-        .map(|x| x.iter().map(|x| x.parse::<u32>().unwrap()).sum::<u32>())
+        .map(|x| x.iter().sum::<u32>())
         .collect::<Vec<u32>>();
     parsed
         .windows(2)
@@ -35,10 +35,10 @@ fn day1_part2(data: &Vec<&str>) -> String {
         .to_string()
 }
 
-pub fn day1_part2_solution(path: &PathBuf) -> String {
-    let data = common::read_file(path);
-    let split: Vec<&str> = data.trim().split("\n").collect();
-    day1_part2(&split)
+#[aoc(year = 2021, day = 1, part = 2)]
+pub fn day1_part2_solution(input: &str) -> Result<Answer, AocError> {
+    let depths = timing::parse(|| parse_input(input))?;
+    Ok(day1_part2(&depths))
 }
 
 #[cfg(test)]
@@ -47,21 +47,21 @@ mod tests {
 
     #[test]
     fn test_day1() {
-        let counter = day1_part1(&vec!["0", "1"]);
+        let counter = day1_part1(&[0, 1]);
         assert_eq!(counter, "1");
 
-        let counter = day1_part1(&vec!["0", "2", "1"]);
+        let counter = day1_part1(&[0, 2, 1]);
         assert_eq!(counter, "1");
 
-        let counter = day1_part1(&vec!["0", "2", "1", "2"]);
+        let counter = day1_part1(&[0, 2, 1, 2]);
         assert_eq!(counter, "2");
+
+        assert!(parse_input("0\n2\nx").is_err());
     }
 
     #[test]
     fn test_day1_part2() {
-        let counter = day1_part2(&vec![
-            "199", "200", "208", "210", "200", "207", "240", "269", "260", "263",
-        ]);
+        let counter = day1_part2(&[199, 200, 208, 210, 200, 207, 240, 269, 260, 263]);
         assert_eq!(counter, "5");
     }
 }
@@ -1,4 +1,8 @@
-use std::path::PathBuf;
+use common::{
+    aoc, timing, visualize,
+    visualize::{Cell, Color, Frame, Render},
+    Answer, AocError, Grid, ParseError, Point, Simulation,
+};
 
 #[derive(Debug, Clone, Copy)]
 struct Octopus {
@@ -39,139 +43,64 @@ impl Octopus {
     }
 
     fn can_flash(&self) -> bool {
-        if self.energy_level > 9 && !self.flashed {
-            true
-        } else {
-            false
-        }
+        self.energy_level > 9 && !self.flashed
     }
 }
 
 struct OctopusSimulation {
-    octopuses: Vec<Vec<Octopus>>,
+    octopuses: Grid<Octopus>,
 }
 
 impl OctopusSimulation {
-    fn new(octopuses: Vec<Vec<Octopus>>) -> Self {
+    fn new(octopuses: Grid<Octopus>) -> Self {
         OctopusSimulation { octopuses }
     }
 
+    #[cfg(test)]
     fn print(&self) {
-        for vec in &self.octopuses {
-            for o in vec {
-                print!("{}", o.energy_level);
-            }
-            println!("");
-        }
+        println!("{}", self.render());
         println!("###########");
     }
 
     fn num_octopus_flashes(&self) -> u32 {
-        self.octopuses
-            .iter()
-            .flatten()
-            .map(|o| o.flash_counter)
-            .sum()
+        self.octopuses.iter().map(|(_, o)| o.flash_counter).sum()
     }
 
     fn is_synchronized_flash(&self) -> bool {
-        self.octopuses.iter().flatten().all(|o| o.energy_level == 0)
+        self.octopuses.iter().all(|(_, o)| o.energy_level == 0)
     }
 
-    fn get_location_of_octopuses_that_can_flash(&self) -> Vec<(usize, usize)> {
-        let mut location_of_octopuses_that_can_flash = Vec::new();
+    fn get_location_of_octopuses_that_can_flash(&self) -> Vec<Point> {
         self.octopuses
             .iter()
-            .enumerate()
-            .for_each(|(x_pos, vec_o)| {
-                vec_o.iter().enumerate().for_each(|(y_pos, o)| {
-                    if o.can_flash() {
-                        location_of_octopuses_that_can_flash.push((x_pos, y_pos));
-                    }
-                })
-            });
-        location_of_octopuses_that_can_flash
+            .filter(|(_, o)| o.can_flash())
+            .map(|(p, _)| p)
+            .collect()
     }
 
     /// position_to_flash MUST be a flashable location
-    fn compute_flash_for_an_octopus(&mut self, position_to_flash: (usize, usize)) {
-        let x_pos = position_to_flash.0;
-        let y_pos = position_to_flash.1;
-        let x_len = self.octopuses.len() - 1;
-        let y_len = self.octopuses[0].len() - 1;
-
-        self.octopuses[x_pos][y_pos].flash();
-
-        let can_x_up = x_pos + 1 <= x_len;
-        let can_x_down = x_pos.checked_sub(1).is_some();
-
-        let can_y_up = y_pos + 1 <= y_len;
-        let can_y_down = y_pos.checked_sub(1).is_some();
-
-        // up
-        if can_x_up {
-            // entity_flashes.push((x_pos + 1, y_pos));
-            self.octopuses[x_pos + 1][y_pos].increment_energy_level();
-        }
-
-        // down
-        if can_x_down {
-            // entity_flashes.push((x_pos - 1, y_pos));
-            self.octopuses[x_pos - 1][y_pos].increment_energy_level();
-        }
-
-        // right
-        if can_y_up {
-            // entity_flashes.push((x_pos, y_pos + 1));
-            self.octopuses[x_pos][y_pos + 1].increment_energy_level();
-        }
-
-        // left
-        if can_y_down {
-            // entity_flashes.push((x_pos, y_pos - 1));
-            self.octopuses[x_pos][y_pos - 1].increment_energy_level();
-        }
-
-        // up_right
-        if can_x_up && can_y_up {
-            // entity_flashes.push((x_pos + 1, y_pos + 1));
-            self.octopuses[x_pos + 1][y_pos + 1].increment_energy_level();
-        }
-
-        // up_left
-        if can_x_up && can_y_down {
-            // entity_flashes.push((x_pos + 1, y_pos - 1));
-            self.octopuses[x_pos + 1][y_pos - 1].increment_energy_level();
-        }
-
-        // down_right
-        if can_x_down && can_y_up {
-            // entity_flashes.push((x_pos - 1, y_pos + 1));
-            self.octopuses[x_pos - 1][y_pos + 1].increment_energy_level();
-        }
-
-        // down_left
-        if can_x_down && can_y_down {
-            // entity_flashes.push((x_pos - 1, y_pos - 1));
-            self.octopuses[x_pos - 1][y_pos - 1].increment_energy_level();
+    fn compute_flash_for_an_octopus(&mut self, position_to_flash: Point) {
+        self.octopuses[position_to_flash].flash();
+        for neighbour in self.octopuses.neighbours8(position_to_flash) {
+            self.octopuses[neighbour].increment_energy_level();
         }
     }
 }
 
-impl Iterator for OctopusSimulation {
-    type Item = Vec<Vec<Octopus>>;
+impl Simulation for OctopusSimulation {
+    type State = Grid<u8>;
 
-    fn next(&mut self) -> Option<Self::Item> {
+    fn step(&mut self) -> bool {
         // 1. Increase energy level by 1
         self.octopuses
-            .iter_mut()
-            .for_each(|vec_e| vec_e.iter_mut().for_each(|e| e.increment_energy_level()));
+            .values_mut()
+            .for_each(|e| e.increment_energy_level());
 
         // 2. Flash all octupus with energy level above 9
         let mut flash_locations = self.get_location_of_octopuses_that_can_flash();
         while !flash_locations.is_empty() {
             for pos in flash_locations {
-                self.compute_flash_for_an_octopus(pos.clone());
+                self.compute_flash_for_an_octopus(pos);
             }
             // println!("####################################################");
             // println!("Parsed Data: {iteration} {:#?}", self.octopuses);
@@ -179,55 +108,94 @@ impl Iterator for OctopusSimulation {
         }
 
         // 3. Reset
-        for vec in &mut self.octopuses {
-            for o in vec {
-                o.end_step();
+        self.octopuses.values_mut().for_each(|o| o.end_step());
+
+        true
+    }
+
+    fn state(&self) -> Self::State {
+        self.octopuses.map(|o| o.energy_level)
+    }
+}
+
+/// Octopuses that just flashed stand out, the others fade in as they charge
+impl Render for OctopusSimulation {
+    fn render(&self) -> Frame {
+        self.octopuses.map(|o| {
+            let symbol = char::from(b'0' + o.energy_level);
+            match o.energy_level {
+                0 => Cell::colored(symbol, Color::Yellow).bold(),
+                1..=4 => Cell::colored(symbol, Color::Blue),
+                _ => Cell::colored(symbol, Color::Cyan),
             }
-        }
+        })
+    }
+
+    fn caption(&self) -> String {
+        format!("{} flashes", self.num_octopus_flashes())
+    }
+}
+
+/// Stops after the first step where every octopus flashes at once
+struct UntilSynchronized(OctopusSimulation);
+
+impl Simulation for UntilSynchronized {
+    type State = Grid<u8>;
 
-        // 4. Return iteration
-        Some(self.octopuses.clone())
+    fn step(&mut self) -> bool {
+        !self.0.is_synchronized_flash() && self.0.step()
     }
+
+    fn state(&self) -> Self::State {
+        self.0.state()
+    }
+}
+
+impl Render for UntilSynchronized {
+    fn render(&self) -> Frame {
+        self.0.render()
+    }
+
+    fn caption(&self) -> String {
+        self.0.caption()
+    }
+}
+
+#[visualize(year = 2021, day = 11)]
+fn octopus_flashes(input: &str) -> Result<UntilSynchronized, AocError> {
+    let octopuses = parse_input(input)?;
+    Ok(UntilSynchronized(OctopusSimulation::new(octopuses)))
 }
 
-fn day11_part1(data: Vec<Vec<Octopus>>) -> u32 {
+fn day11_part1(data: Grid<Octopus>) -> u32 {
     let mut octopus_simulation = OctopusSimulation::new(data);
-    octopus_simulation.nth(99).unwrap();
+    octopus_simulation.run(100);
     octopus_simulation.num_octopus_flashes()
 }
 
-fn parse_values_from_file(path: &PathBuf) -> Vec<Vec<Octopus>> {
-    common::read_file(path)
-        .trim()
-        .split("\n")
-        .map(|x| {
-            x.trim()
-                .as_bytes()
-                .iter()
-                .map(|c| Octopus::new(c - 0x30))
-                .collect::<Vec<Octopus>>()
-        })
-        .collect::<Vec<Vec<Octopus>>>()
+fn parse_input(input: &str) -> Result<Grid<Octopus>, ParseError> {
+    Grid::parse(input, "an energy level between 0 and 9", |c| {
+        c.to_digit(10).map(|d| Octopus::new(d as u8))
+    })
 }
 
-pub fn day11_part1_solution(path: &PathBuf) -> String {
-    let data = parse_values_from_file(path);
-    day11_part1(data).to_string()
+#[aoc(year = 2021, day = 11, part = 1)]
+pub fn day11_part1_solution(input: &str) -> Result<Answer, AocError> {
+    let data = timing::parse(|| parse_input(input))?;
+    Ok(day11_part1(data).to_string())
 }
 
-fn day11_part2(data: Vec<Vec<Octopus>>) -> u32 {
+fn day11_part2(data: Grid<Octopus>) -> u32 {
     let mut octopus_simulation = OctopusSimulation::new(data);
-    let mut counter = 0;
-    while !octopus_simulation.is_synchronized_flash() {
-        octopus_simulation.next();
-        counter += 1;
-    }
-    counter
+    octopus_simulation
+        .run_until(OctopusSimulation::is_synchronized_flash)
+        .unwrap() as u32
 }
 
-pub fn day11_part2_solution(path: &PathBuf) -> String {
-    let data = parse_values_from_file(path);
-    day11_part2(data).to_string()
+#[aoc(year = 2021, day = 11, part = 2)]
+pub fn day11_part2_solution(input: &str) -> Result<Answer, AocError> {
+    let data = timing::parse(|| parse_input(input))?;
+    Ok(day11_part2(data).to_string())
 }
 
 #[cfg(test)]
@@ -247,16 +215,17 @@ mod tests {
                     .collect::<Vec<Octopus>>()
             })
             .collect::<Vec<Vec<Octopus>>>();
+        let parsed_data = Grid::from_rows(parsed_data);
 
         {
             let mut octopus_simulation = OctopusSimulation::new(parsed_data.clone());
-            octopus_simulation.nth(0).unwrap();
+            octopus_simulation.step();
             octopus_simulation.print();
         }
 
         {
             let mut octopus_simulation = OctopusSimulation::new(parsed_data.clone());
-            octopus_simulation.nth(1).unwrap();
+            octopus_simulation.run(2);
             octopus_simulation.print();
         }
     }
@@ -285,10 +254,11 @@ mod tests {
                     .collect::<Vec<Octopus>>()
             })
             .collect::<Vec<Vec<Octopus>>>();
+        let parsed_data = Grid::from_rows(parsed_data);
 
         {
             let mut octopus_simulation = OctopusSimulation::new(parsed_data.clone());
-            octopus_simulation.nth(9).unwrap();
+            octopus_simulation.run(10);
             octopus_simulation.print();
 
             assert_eq!(octopus_simulation.num_octopus_flashes(), 204);
@@ -296,15 +266,14 @@ mod tests {
 
         {
             let mut octopus_simulation = OctopusSimulation::new(parsed_data.clone());
-            octopus_simulation.nth(99).unwrap();
+            octopus_simulation.run(100);
             octopus_simulation.print();
 
             assert_eq!(octopus_simulation.num_octopus_flashes(), 1656);
         }
 
         {
-            let solution =
-                day11_part1_solution(&PathBuf::new().join("inputs").join("day11_input.txt"));
+            let solution = day11_part1_solution(&common::read_input(2021, 11).unwrap()).unwrap();
             println!("Day11 Part1: {solution}");
         }
     }
@@ -333,26 +302,22 @@ mod tests {
                     .collect::<Vec<Octopus>>()
             })
             .collect::<Vec<Vec<Octopus>>>();
+        let parsed_data = Grid::from_rows(parsed_data);
 
         {
             let mut octopus_simulation = OctopusSimulation::new(parsed_data.clone());
 
-            octopus_simulation.nth(194).unwrap();
+            octopus_simulation.run(195);
             assert!(octopus_simulation.is_synchronized_flash());
         }
 
         {
             let mut octopus_simulation = OctopusSimulation::new(parsed_data.clone());
-            let mut counter = 0;
-            while !octopus_simulation.is_synchronized_flash() {
-                octopus_simulation.next();
-                counter += 1;
-            }
-            assert_eq!(counter, 195);
+            let counter = octopus_simulation.run_until(OctopusSimulation::is_synchronized_flash);
+            assert_eq!(counter, Some(195));
         }
         {
-            let solution =
-                day11_part2_solution(&PathBuf::new().join("inputs").join("day11_input.txt"));
+            let solution = day11_part2_solution(&common::read_input(2021, 11).unwrap()).unwrap();
             println!("Day11 Part2: {solution}");
         }
     }
@@ -1,16 +1,13 @@
-use std::{collections::HashSet, path::PathBuf};
+use std::collections::HashSet;
 
-#[derive(Debug)]
-struct Point {
-    x: usize,
-    y: usize,
-}
-
-impl Point {
-    fn new(x: usize, y: usize) -> Self {
-        Self { x, y }
-    }
-}
+use common::{
+    aoc,
+    combinators::{self, integer, lines, map, pair, prefixed},
+    ocr::{self, OcrError},
+    timing, visualize,
+    visualize::{Cell, Color, Frame, Render},
+    Answer, AocError, Grid, ParseError, Point, Simulation, Span,
+};
 
 #[derive(Debug)]
 struct Instruction {
@@ -34,11 +31,7 @@ impl TransparentPaper {
     }
 
     fn count_points(&self) -> usize {
-        let mut set = HashSet::new();
-        for p in &self.points {
-            set.insert((p.x, p.y));
-        }
-        set.len()
+        self.points.iter().collect::<HashSet<_>>().len()
     }
 
     fn perform_instruction(&mut self, instruction: &Instruction) {
@@ -69,96 +62,129 @@ impl TransparentPaper {
         Point::new(max_x + 1, max_y + 1)
     }
 
-    fn graph_as_string(&self) -> String {
-        // Initialize
-        let max_points = self.get_max_points();
-        let mut final_vec = vec![vec!["."; max_points.x]; max_points.y];
+    /// The letters the dots draw once every fold is done
+    fn read_code(&self) -> Result<String, OcrError> {
+        ocr::read_points(self.points.iter().copied())
+    }
 
-        // Plot points
-        self.points.iter().for_each(|p| final_vec[p.y][p.x] = "#");
+    #[allow(dead_code)]
+    fn print_graph(&self) {
+        println!("{}", self.render());
+    }
+}
 
-        // Final output
-        let final_str = final_vec
-            .iter()
-            .map(|l| l.join(""))
-            .collect::<Vec<String>>();
-        format!("{:#?}", final_str)
+impl Render for TransparentPaper {
+    fn render(&self) -> Frame {
+        let size = self.get_max_points();
+        let mut frame = Grid::new(size.x, size.y, Cell::new('.'));
+        for &point in &self.points {
+            frame[point] = Cell::colored('#', Color::Yellow).bold();
+        }
+        frame
     }
 
-    fn print_graph(&self) {
-        println!("{}", self.graph_as_string());
+    fn caption(&self) -> String {
+        format!("{} dots", self.count_points())
     }
 }
 
-fn parse_values_from_file(path: &PathBuf) -> (Vec<Point>, Vec<Instruction>) {
-    let split_string = common::read_file(path)
-        .trim()
-        .split("\n")
-        .map(|s| s.trim().to_owned())
-        .collect::<Vec<String>>();
+/// The paper folded one instruction at a time
+struct Folding {
+    paper: TransparentPaper,
+    instructions: Vec<Instruction>,
+    folds: usize,
+}
 
-    let mut points = Vec::new();
-    let mut instruction_start_line = None;
+impl Simulation for Folding {
+    type State = (Vec<Point>, usize);
 
-    for (index, line) in split_string.iter().enumerate() {
-        let trimmed_line = line.trim();
-        if trimmed_line.is_empty() {
-            instruction_start_line = Some(index);
-            break;
-        }
+    fn step(&mut self) -> bool {
+        let Some(instruction) = self.instructions.get(self.folds) else {
+            return false;
+        };
+        self.paper.perform_instruction(instruction);
+        self.folds += 1;
+        true
+    }
+
+    fn state(&self) -> Self::State {
+        let mut points = self.paper.points.clone();
+        points.sort_unstable();
+        points.dedup();
+        (points, self.folds)
+    }
+}
 
-        let point_str = trimmed_line.split(",").collect::<Vec<&str>>();
-        // println!("Point String: {:?}", point_str);
-        let x = point_str[0].parse::<usize>().unwrap();
-        let y = point_str[1].parse::<usize>().unwrap();
-        points.push(Point::new(x, y));
+impl Render for Folding {
+    fn render(&self) -> Frame {
+        self.paper.render()
     }
 
-    let mut instructions = Vec::new();
+    fn caption(&self) -> String {
+        format!(
+            "fold {}/{}, {}",
+            self.folds,
+            self.instructions.len(),
+            self.paper.caption()
+        )
+    }
+}
 
-    for line in split_string
-        .iter()
-        .skip(instruction_start_line.unwrap() + 1)
-    {
-        let instruction_str = line.split("fold along ").collect::<Vec<&str>>()[1]
-            .split("=")
-            .collect::<Vec<&str>>();
+#[visualize(year = 2021, day = 13)]
+fn folding(input: &str) -> Result<Folding, AocError> {
+    let (points, instructions) = parse_input(input)?;
+    Ok(Folding {
+        paper: TransparentPaper::new(points),
+        instructions,
+        folds: 0,
+    })
+}
 
-        let instruction = Instruction::new(
-            instruction_str[1].parse::<usize>().unwrap(),
-            instruction_str[0].to_owned(),
-        );
-        instructions.push(instruction);
+fn parse_axis(axis: Span) -> Result<String, ParseError> {
+    match axis.as_str() {
+        "x" | "y" => Ok(axis.to_string()),
+        _ => Err(axis.error("`x` or `y`")),
     }
+}
 
-    (points, instructions)
+fn parse_input(input: &str) -> Result<(Vec<Point>, Vec<Instruction>), ParseError> {
+    let point = map(pair(integer::<usize>, ",", integer::<usize>), |(x, y)| {
+        Point::new(x, y)
+    });
+    let instruction = map(
+        prefixed("fold along", pair(parse_axis, "=", integer::<usize>)),
+        |(axis, index)| Instruction::new(index, axis),
+    );
+    combinators::parse(input, pair(lines(point), "\n\n", lines(instruction)))
 }
 
 fn day13_part1(points: Vec<Point>, instructions: Vec<Instruction>) -> usize {
     let mut transparent_paper = TransparentPaper::new(points);
-    if instructions.len() > 0 {
+    if !instructions.is_empty() {
         transparent_paper.perform_instruction(&instructions[0]);
     }
     // transparent_paper.print_graph();
     transparent_paper.count_points()
 }
 
-pub fn day13_part1_solution(path: &PathBuf) -> String {
-    let (points, instructions) = parse_values_from_file(path);
-    day13_part1(points, instructions).to_string()
+#[aoc(year = 2021, day = 13, part = 1)]
+pub fn day13_part1_solution(input: &str) -> Result<Answer, AocError> {
+    let (points, instructions) = timing::parse(|| parse_input(input))?;
+    Ok(day13_part1(points, instructions).to_string())
 }
 
-fn day13_part2(points: Vec<Point>, instructions: Vec<Instruction>) -> String {
+fn day13_part2(points: Vec<Point>, instructions: Vec<Instruction>) -> Result<String, OcrError> {
     let mut transparent_paper = TransparentPaper::new(points);
     for instruction in instructions {
         transparent_paper.perform_instruction(&instruction);
     }
-    transparent_paper.graph_as_string()
+    transparent_paper.read_code()
 }
 
-pub fn day13_part2_solution(path: &PathBuf) -> String {
-    let (points, instructions) = parse_values_from_file(path);
-    day13_part2(points, instructions)
+#[aoc(year = 2021, day = 13, part = 2)]
+pub fn day13_part2_solution(input: &str) -> Result<Answer, AocError> {
+    let (points, instructions) = timing::parse(|| parse_input(input))?;
+    Ok(day13_part2(points, instructions)?)
 }
 
 #[cfg(test)]
@@ -232,19 +258,41 @@ mod tests {
         assert_eq!(solution, 17);
     }
 
+    #[test]
+    fn test_folding_render() {
+        let (points, instructions) = test_parse();
+        let mut folding = Folding {
+            paper: TransparentPaper::new(points),
+            instructions,
+            folds: 0,
+        };
+        assert_eq!(folding.render().height(), 15);
+        assert_eq!(folding.run(5), 2);
+        assert_eq!(folding.caption(), "fold 2/2, 16 dots");
+        assert_eq!(
+            folding.render().to_string(),
+            "#####\n#...#\n#...#\n#...#\n#####"
+        );
+    }
+
     #[test]
     fn test_day13_part1_solution() {
-        let solution = day13_part1_solution(&PathBuf::new().join("inputs").join("day13_input.txt"));
+        let solution = day13_part1_solution(&common::read_input(2021, 13).unwrap()).unwrap();
         println!("Solution: {}", solution);
     }
 
     #[test]
     fn test_day13_part2_solution() {
+        // NOTE, The example folds into a square, which isn't a letter
         let (points, instructions) = test_parse();
-        day13_part2(points, instructions);
+        assert_eq!(
+            day13_part2(points, instructions),
+            Err(OcrError::UnsupportedHeight(5))
+        );
 
-        //cargo test --package all --bin all -- day13::tests::test_day13_part2_solution --exact --nocapture
-        let solution = day13_part2_solution(&PathBuf::new().join("inputs").join("day13_input.txt"));
+        //cargo test --package aoc_2021 --lib -- day13::tests::test_day13_part2_solution --exact --nocapture
+        let solution = day13_part2_solution(&common::read_input(2021, 13).unwrap()).unwrap();
         println!("Solution: {}", solution);
+        assert_eq!(solution, "PERCGJPB");
     }
 }
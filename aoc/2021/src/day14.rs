@@ -1,4 +1,11 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::collections::HashMap;
+
+use common::{
+    aoc,
+    combinators::{self, key_values, pair, text},
+    rewrite::{ElementCounts, PairInsertion},
+    timing, Answer, AocError, ParseError, Simulation, Span,
+};
 
 struct PolymerTemplate<'a> {
     start: String,
@@ -14,12 +21,7 @@ impl<'a> PolymerTemplate<'a> {
         let mut count_map: HashMap<char, usize> = HashMap::new();
 
         self.start.chars().for_each(|a| {
-            if count_map.contains_key(&a) {
-                let v = count_map.get_mut(&a).unwrap();
-                *v += 1;
-            } else {
-                count_map.insert(a, 1);
-            }
+            *count_map.entry(a).or_insert(0) += 1;
         });
         count_map
     }
@@ -36,65 +38,63 @@ impl<'a> PolymerTemplate<'a> {
     }
 }
 
-impl<'a> Iterator for PolymerTemplate<'a> {
-    type Item = ();
+impl<'a> Simulation for PolymerTemplate<'a> {
+    type State = String;
 
-    fn next(&mut self) -> Option<Self::Item> {
+    fn step(&mut self) -> bool {
         let mut counter = 0;
         let mut vec = Vec::new();
         (0..self.start.len() - 1).for_each(|index| {
             let key = &self.start[index..index + 2];
-            match self.map.get(key) {
-                Some(value) => {
-                    vec.push((index + 1 + counter, value));
-                    counter += 1;
-                }
-                None => {}
+            if let Some(value) = self.map.get(key) {
+                vec.push((index + 1 + counter, value));
+                counter += 1;
             }
         });
 
         for (index, value) in vec {
-            self.start.insert(index, value.clone());
+            self.start.insert(index, *value);
         }
-        Some(())
+        true
+    }
+
+    fn state(&self) -> Self::State {
+        self.start.clone()
     }
 }
 
-fn parse_values_from_file(path: &PathBuf) -> (String, HashMap<String, char>) {
-    let data = common::read_file(path);
-    let mut split_iter = data.trim().split("\n");
-    let start = split_iter
-        .by_ref()
-        .take(1)
-        .map(|s| s.trim())
-        .collect::<Vec<&str>>()[0]
-        .to_owned();
-    // println!("Start: {:?}", start);
-
-    let mut map = HashMap::new();
-    split_iter
-        .by_ref()
-        .skip(1)
-        .map(|line| line.trim().split(" -> ").collect::<Vec<&str>>())
-        .for_each(|line| {
-            let key = line[0].to_owned();
-            let value = line[1].chars().last().unwrap(); // There must only be ONE char here after parsing
-            map.insert(key, value);
-        });
-    // println!("Map: {:?}", map);
+fn parse_pair(pair: Span) -> Result<String, ParseError> {
+    match pair.as_str().chars().count() {
+        2 => Ok(pair.to_string()),
+        _ => Err(pair.error("a pair of elements")),
+    }
+}
 
-    (start, map)
+fn parse_input(input: &str) -> Result<(String, HashMap<String, char>), ParseError> {
+    // NOTE, A rule inserts exactly ONE char between a pair
+    let rules = key_values(parse_pair, "->", |element: Span| element.parse::<char>());
+    let (start, map) = combinators::parse(input, pair(text, "\n\n", rules))?;
+    Ok((start.to_string(), map))
 }
 
 fn day14_part1(start: String, map: &HashMap<String, char>) -> usize {
-    let mut polymer_template = PolymerTemplate::new(start, &map);
-    polymer_template.nth(9).unwrap();
+    let mut polymer_template = PolymerTemplate::new(start, map);
+    polymer_template.run(10);
     polymer_template.get_diff()
 }
 
-pub fn day14_part1_solution(path: &PathBuf) -> String {
-    let (start, map) = parse_values_from_file(path);
-    day14_part1(start, &map).to_string()
+#[aoc(year = 2021, day = 14, part = 1)]
+pub fn day14_part1_solution(input: &str) -> Result<Answer, AocError> {
+    let (start, map) = timing::parse(|| parse_input(input))?;
+    Ok(day14_part1(start, &map).to_string())
+}
+
+#[aoc(year = 2021, day = 14, part = 1, variant = "chain")]
+pub fn day14_part1_chain_solution(input: &str) -> Result<Answer, AocError> {
+    let (start, map) = timing::parse(|| parse_input(input))?;
+    let mut chain_polymer_template = ChainPolymerTemplate::new(start, &map);
+    chain_polymer_template.run(10);
+    Ok(chain_polymer_template.get_diff().to_string())
 }
 
 /// ChainPolymerTemplate is a more efficient implementation of PolymerTemplate
@@ -124,24 +124,14 @@ impl<'a> ChainPolymerTemplate<'a> {
                 slice.to_string()
             })
             .for_each(|data| {
-                if chain.contains_key(&data) {
-                    let value = chain.get_mut(&data).unwrap();
-                    *value += 1;
-                } else {
-                    chain.insert(data, 1);
-                }
+                *chain.entry(data).or_insert(0) += 1;
             });
 
         // Initialize count
         // For example: {N:2, C:1, B:1}
         let mut count = HashMap::new();
         start.chars().for_each(|c| {
-            if count.contains_key(&c) {
-                let value = count.get_mut(&c).unwrap();
-                *value += 1;
-            } else {
-                count.insert(c, 1);
-            }
+            *count.entry(c).or_insert(0) += 1;
         });
         Self { chain, map, count }
     }
@@ -158,11 +148,12 @@ impl<'a> ChainPolymerTemplate<'a> {
     }
 }
 
-impl<'a> Iterator for ChainPolymerTemplate<'a> {
-    type Item = ();
+impl<'a> Simulation for ChainPolymerTemplate<'a> {
+    /// Sorted pair and element counts
+    type State = (Vec<(String, usize)>, Vec<(char, usize)>);
 
     // There might be multiple chains (NN, NC, CB etc)
-    fn next(&mut self) -> Option<Self::Item> {
+    fn step(&mut self) -> bool {
         // For every next iteration
         // Check the map for new "char" to be added in between
         // Update the chain with the new chars added
@@ -170,21 +161,11 @@ impl<'a> Iterator for ChainPolymerTemplate<'a> {
 
         let mut new_chain = HashMap::new();
         let mut new_maps_insert = |key: String, amount: usize| {
-            if new_chain.contains_key(&key) {
-                let value = new_chain.get_mut(&key).unwrap();
-                *value += amount;
-            } else {
-                new_chain.insert(key, amount);
-            }
+            *new_chain.entry(key).or_insert(0) += amount;
         };
 
         let mut count_insert = |ch: char, amount: usize| {
-            if self.count.contains_key(&ch) {
-                let value = self.count.get_mut(&ch).unwrap();
-                *value += amount;
-            } else {
-                self.count.insert(ch, amount);
-            }
+            *self.count.entry(ch).or_insert(0) += amount;
         };
 
         self.chain
@@ -192,13 +173,13 @@ impl<'a> Iterator for ChainPolymerTemplate<'a> {
             .filter(|&(chain_key, _)| self.map.contains_key(chain_key))
             .for_each(|(chain_key, chain_value)| {
                 let map_value = self.map.get(chain_key).unwrap();
-                count_insert(map_value.clone(), chain_value.clone());
+                count_insert(*map_value, *chain_value);
 
                 let ch1 =
                     chain_key.chars().take(1).last().unwrap().to_string() + &map_value.to_string();
                 let ch2 = map_value.to_string() + &chain_key.chars().last().unwrap().to_string();
-                new_maps_insert(ch1, chain_value.clone());
-                new_maps_insert(ch2, chain_value.clone());
+                new_maps_insert(ch1, *chain_value);
+                new_maps_insert(ch2, *chain_value);
             });
 
         // println!("Chain: {:?}", self.chain);
@@ -207,23 +188,73 @@ impl<'a> Iterator for ChainPolymerTemplate<'a> {
         // println!("-------------------------");
         self.chain = new_chain;
 
-        Some(())
+        true
+    }
+
+    fn state(&self) -> Self::State {
+        let mut chain = self.chain.clone().into_iter().collect::<Vec<_>>();
+        let mut count = self.count.clone().into_iter().collect::<Vec<_>>();
+        chain.sort();
+        count.sort();
+        (chain, count)
     }
 }
 
-fn day14_part2(start: String, map: &HashMap<String, char>) -> usize {
-    let mut chain_polymer_template = ChainPolymerTemplate::new(start, map);
-    chain_polymer_template.nth(39);
-    chain_polymer_template.get_diff()
+fn pair_insertion(start: &str, map: &HashMap<String, char>) -> PairInsertion {
+    // NOTE, Pairs are checked to be two chars by the parser
+    let rules = map.iter().map(|(pair, &element)| {
+        let mut chars = pair.chars();
+        let pair = (chars.next().unwrap(), chars.next().unwrap());
+        (pair, element.to_string())
+    });
+    PairInsertion::new(start, rules)
 }
 
-pub fn day14_part2_solution(path: &PathBuf) -> String {
-    let (start, map) = parse_values_from_file(path);
-    day14_part2(start, &map).to_string()
+/// Most common minus least common element
+fn diff(counts: &ElementCounts) -> u128 {
+    let max = counts.values().max().unwrap();
+    let min = counts.values().min().unwrap();
+    max - min
+}
+
+#[aoc(year = 2021, day = 14, part = 1, variant = "matrix")]
+pub fn day14_part1_matrix_solution(input: &str) -> Result<Answer, AocError> {
+    let (start, map) = timing::parse(|| parse_input(input))?;
+    let counts = pair_insertion(&start, &map).element_counts_by_squaring(10);
+    Ok(diff(&counts.unwrap()).to_string())
+}
+
+fn day14_part2(start: String, map: &HashMap<String, char>) -> u128 {
+    // NOTE, A polymer doubles at most every step, 40 steps fit in a u128
+    let counts = pair_insertion(&start, map).element_counts(40).unwrap();
+    diff(&counts)
+}
+
+#[aoc(year = 2021, day = 14, part = 2)]
+pub fn day14_part2_solution(input: &str) -> Result<Answer, AocError> {
+    let (start, map) = timing::parse(|| parse_input(input))?;
+    Ok(day14_part2(start, &map).to_string())
+}
+
+#[aoc(year = 2021, day = 14, part = 2, variant = "chain")]
+pub fn day14_part2_chain_solution(input: &str) -> Result<Answer, AocError> {
+    let (start, map) = timing::parse(|| parse_input(input))?;
+    let mut chain_polymer_template = ChainPolymerTemplate::new(start, &map);
+    chain_polymer_template.run(40);
+    Ok(chain_polymer_template.get_diff().to_string())
+}
+
+#[aoc(year = 2021, day = 14, part = 2, variant = "matrix")]
+pub fn day14_part2_matrix_solution(input: &str) -> Result<Answer, AocError> {
+    let (start, map) = timing::parse(|| parse_input(input))?;
+    let counts = pair_insertion(&start, &map).element_counts_by_squaring(40);
+    Ok(diff(&counts.unwrap()).to_string())
 }
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use super::*;
 
     fn test_parse() -> (String, HashMap<String, char>) {
@@ -275,7 +306,7 @@ CN -> C";
         let solution = day14_part1(start.clone(), &map);
         assert_eq!(solution, 1588);
 
-        let solution = day14_part1_solution(&PathBuf::new().join("inputs").join("day14_input.txt"));
+        let solution = day14_part1_solution(&common::read_input(2021, 14).unwrap()).unwrap();
         println!("Solution: {}", solution);
     }
 
@@ -283,7 +314,7 @@ CN -> C";
     fn test_day14_part1_method2() {
         let (start, map) = test_parse();
         let mut chain_polymer_template = ChainPolymerTemplate::new(start.clone(), &map);
-        chain_polymer_template.nth(9);
+        chain_polymer_template.run(10);
         assert_eq!(chain_polymer_template.get_diff(), 1588);
     }
 
@@ -292,7 +323,55 @@ CN -> C";
         let (start, map) = test_parse();
         assert_eq!(day14_part2(start, &map), 2188189693529);
 
-        let solution = day14_part2_solution(&PathBuf::new().join("inputs").join("day14_input.txt"));
+        let solution = day14_part2_solution(&common::read_input(2021, 14).unwrap()).unwrap();
         println!("Solution: {}", solution);
     }
+
+    #[test]
+    fn test_pair_insertion_prefix() {
+        let (start, map) = test_parse();
+        let pair_insertion = pair_insertion(&start, &map);
+        let mut polymer_template = PolymerTemplate::new(start, &map);
+        for steps in 0..=10 {
+            assert_eq!(
+                pair_insertion.prefix(steps, usize::MAX),
+                polymer_template.state()
+            );
+            polymer_template.step();
+        }
+        assert_eq!(pair_insertion.prefix(40, 12), "NBBNBBNBBNBB");
+    }
+
+    /// A template and some insertion rules over a small alphabet, so that
+    /// most pairs of the template have a rule
+    fn puzzle() -> impl Strategy<Value = String> {
+        let rules = prop::collection::btree_map("[A-D]{2}", "[A-D]", 1..16);
+        ("[A-D]{1,6}", rules).prop_map(|(start, rules)| {
+            let rules = rules
+                .iter()
+                .map(|(pair, element)| format!("{} -> {}", pair, element))
+                .collect::<Vec<_>>();
+            format!("{}\n\n{}", start, rules.join("\n"))
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn test_day14_variants(input in puzzle()) {
+            common::assert_implementations_agree(2021, 14, &input);
+        }
+
+        #[test]
+        fn test_pair_insertion_prefix_matches_naive(input in puzzle(), steps in 0..6usize) {
+            let (start, map) = parse_input(&input).unwrap();
+            let expected = {
+                let mut polymer_template = PolymerTemplate::new(start.clone(), &map);
+                polymer_template.run(steps);
+                polymer_template.state()
+            };
+            let pair_insertion = pair_insertion(&start, &map);
+            prop_assert_eq!(pair_insertion.prefix(steps, usize::MAX), expected.clone());
+            prop_assert_eq!(pair_insertion.prefix(steps, 5), &expected[..5.min(expected.len())]);
+        }
+    }
 }
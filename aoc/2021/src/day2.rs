@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use common::{aoc, timing, Answer, AocError, ParseError, Span};
 
 #[derive(Debug, Clone, Copy)]
 struct Value {
@@ -40,27 +40,45 @@ impl Value {
     }
 }
 
-fn day2_part1(data: &Vec<&str>) -> String {
-    let fvalue = data.iter().fold(Value::new(), |acc, x| {
-        let splitstr: Vec<&str> = x.trim().split(" ").collect();
-        let identifier = splitstr[0];
-        let value = splitstr[1].parse::<u32>().unwrap();
+#[derive(Debug)]
+enum Command {
+    Forward(u32),
+    Down(u32),
+    Up(u32),
+}
+
+/// Parses a command such as `forward 5`
+fn parse_command(line: Span) -> Result<Command, ParseError> {
+    let (identifier, value) = line.trim().split_once(" ")?;
+    let value = value.parse::<u32>()?;
+    match identifier.as_str() {
+        "forward" => Ok(Command::Forward(value)),
+        "down" => Ok(Command::Down(value)),
+        "up" => Ok(Command::Up(value)),
+        _ => Err(identifier.error("`forward`, `down` or `up`")),
+    }
+}
 
+fn parse_input(input: &str) -> Result<Vec<Command>, ParseError> {
+    Span::new(input).trim().lines().map(parse_command).collect()
+}
+
+fn day2_part1(data: &[Command]) -> String {
+    let fvalue = data.iter().fold(Value::new(), |acc, x| {
         // Return one of these values
-        match identifier {
-            "forward" => acc.add_length(value),
-            "down" => acc.add_depth(value),
-            "up" => acc.remove_depth(value),
-            _ => Value::new(),
+        match *x {
+            Command::Forward(value) => acc.add_length(value),
+            Command::Down(value) => acc.add_depth(value),
+            Command::Up(value) => acc.remove_depth(value),
         }
     });
     fvalue.product().to_string()
 }
 
-pub fn day2_part1_solution(path: &PathBuf) -> String {
-    let data = common::read_file(path);
-    let split: Vec<&str> = data.trim().split("\n").collect();
-    day2_part1(&split)
+#[aoc(year = 2021, day = 2, part = 1)]
+pub fn day2_part1_solution(input: &str) -> Result<Answer, AocError> {
+    let commands = timing::parse(|| parse_input(input))?;
+    Ok(day2_part1(&commands))
 }
 
 //
@@ -104,56 +122,44 @@ impl ValueWithAim {
     }
 }
 
-fn day2_part2(data: &Vec<&str>) -> String {
+fn day2_part2(data: &[Command]) -> String {
     let fvalue = data.iter().fold(ValueWithAim::new(), |acc, x| {
-        let splitstr: Vec<&str> = x.trim().split(" ").collect();
-        let identifier = splitstr[0];
-        let value = splitstr[1].parse::<u32>().unwrap();
-
         // Return one of these values
-        match identifier {
-            "forward" => acc.compute_forward(value),
-            "down" => acc.increase_aim(value),
-            "up" => acc.decrease_aim(value),
-            _ => ValueWithAim::new(),
+        match *x {
+            Command::Forward(value) => acc.compute_forward(value),
+            Command::Down(value) => acc.increase_aim(value),
+            Command::Up(value) => acc.decrease_aim(value),
         }
     });
     fvalue.value.product().to_string()
 }
 
-pub fn day2_part2_solution(path: &PathBuf) -> String {
-    let data = common::read_file(path);
-    let split: Vec<&str> = data.trim().split("\n").collect();
-    day2_part2(&split)
+#[aoc(year = 2021, day = 2, part = 2)]
+pub fn day2_part2_solution(input: &str) -> Result<Answer, AocError> {
+    let commands = timing::parse(|| parse_input(input))?;
+    Ok(day2_part2(&commands))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const INPUT: &str = "forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2";
+
     #[test]
     fn test_day2() {
-        let counter = day2_part1(&vec![
-            "forward 5",
-            "down 5",
-            "forward 8",
-            "up 3",
-            "down 8",
-            "forward 2",
-        ]);
+        let counter = day2_part1(&parse_input(INPUT).unwrap());
         assert_eq!(counter, "150");
+
+        let error = parse_input("forward 5\nsideways 2").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 1));
+        assert_eq!(error.expected, "`forward`, `down` or `up`");
+        assert_eq!(parse_input("forward").unwrap_err().expected, "` `");
     }
 
     #[test]
     fn test_day2_part2() {
-        let counter = day2_part2(&vec![
-            "forward 5",
-            "down 5",
-            "forward 8",
-            "up 3",
-            "down 8",
-            "forward 2",
-        ]);
+        let counter = day2_part2(&parse_input(INPUT).unwrap());
         assert_eq!(counter, "900");
     }
 }
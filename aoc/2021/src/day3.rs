@@ -1,7 +1,26 @@
-use std::path::PathBuf;
+use common::{aoc, timing, Answer, AocError, ParseError, Span};
+
+/// Every line is a binary number, all of the same width
+fn parse_input(input: &str) -> Result<Vec<&str>, ParseError> {
+    let split = Span::new(input)
+        .trim()
+        .lines()
+        .map(|x| x.trim())
+        .collect::<Vec<Span>>();
+    let width = split[0].as_str().len();
+    for line in &split {
+        if let Some((digit, _)) = line.chars().find(|&(_, c)| c != '0' && c != '1') {
+            return Err(digit.error("a binary digit"));
+        }
+        if line.as_str().len() != width {
+            return Err(line.error(format!("{} binary digits", width)));
+        }
+    }
+    Ok(split.iter().map(|x| x.as_str()).collect())
+}
 
 // Helper function
-fn sum_bits(data: &Vec<&str>) -> Vec<u32> {
+fn sum_bits(data: &[&str]) -> Vec<u32> {
     data.iter()
         .map(|x| {
             x.trim()
@@ -20,11 +39,11 @@ fn sum_bits(data: &Vec<&str>) -> Vec<u32> {
 }
 
 // Helper function
-fn sum_bit_at_position(data: &Vec<&str>, pos: usize) -> u32 {
+fn sum_bit_at_position(data: &[&str], pos: usize) -> u32 {
     data.iter().map(|x| (x.as_bytes()[pos] - 0x30) as u32).sum()
 }
 
-fn day3_part1(data: &Vec<&str>) -> String {
+fn day3_part1(data: &[&str]) -> String {
     let length_compare = data.len() as f32 / 2.0;
 
     // Compute gamma and epsilon
@@ -40,7 +59,7 @@ fn day3_part1(data: &Vec<&str>) -> String {
         })
         .rev()
         .enumerate()
-        .map(|(index, value)| (value * (1 << index)))
+        .map(|(index, value)| value * (1 << index))
         .sum::<u32>();
     let epsilon = sum_bits
         .iter()
@@ -53,18 +72,18 @@ fn day3_part1(data: &Vec<&str>) -> String {
         })
         .rev()
         .enumerate()
-        .map(|(index, value)| (value * (1 << index)))
+        .map(|(index, value)| value * (1 << index))
         .sum::<u32>();
     (gamma * epsilon).to_string()
 }
 
-pub fn day3_part1_solution(path: &PathBuf) -> String {
-    let data = common::read_file(path);
-    let split = data.trim().split("\n").collect::<Vec<&str>>();
-    day3_part1(&split)
+#[aoc(year = 2021, day = 3, part = 1)]
+pub fn day3_part1_solution(input: &str) -> Result<Answer, AocError> {
+    let split = timing::parse(|| parse_input(input))?;
+    Ok(day3_part1(&split))
 }
 
-fn day3_part2(data: &Vec<&str>) -> String {
+fn day3_part2(data: &[&str]) -> String {
     let max_from_sum = |value, comp| {
         if value >= comp {
             1
@@ -89,7 +108,7 @@ fn day3_part2(data: &Vec<&str>) -> String {
             (x.as_bytes()[counter] - 0x30) as u32
                 == max_from_sum(sum_bit_at_pos as f32, data.len() as f32 / 2.0)
         })
-        .map(|x| *x)
+        .copied()
         .collect::<Vec<&str>>();
 
     let mut co2_filtered = data
@@ -98,7 +117,7 @@ fn day3_part2(data: &Vec<&str>) -> String {
             (x.as_bytes()[counter] - 0x30) as u32
                 == min_from_sum(sum_bit_at_pos as f32, data.len() as f32 / 2.0)
         })
-        .map(|x| *x)
+        .copied()
         .collect::<Vec<&str>>();
 
     while o2_filtered.len() != 1 && counter <= data.len() {
@@ -110,7 +129,7 @@ fn day3_part2(data: &Vec<&str>) -> String {
                 (x.as_bytes()[counter] - 0x30) as u32
                     == max_from_sum(sum_bit_at_pos as f32, o2_filtered.len() as f32 / 2.0)
             })
-            .map(|x| *x)
+            .copied()
             .collect::<Vec<&str>>();
     }
 
@@ -124,7 +143,7 @@ fn day3_part2(data: &Vec<&str>) -> String {
                 (x.as_bytes()[counter] - 0x30) as u32
                     == min_from_sum(sum_bit_at_pos as f32, co2_filtered.len() as f32 / 2.0)
             })
-            .map(|x| *x)
+            .copied()
             .collect::<Vec<&str>>();
     }
 
@@ -134,10 +153,10 @@ fn day3_part2(data: &Vec<&str>) -> String {
     (o2 * co2).to_string()
 }
 
-pub fn day3_part2_solution(path: &PathBuf) -> String {
-    let data = common::read_file(path);
-    let split = data.trim().split("\n").collect::<Vec<&str>>();
-    day3_part2(&split)
+#[aoc(year = 2021, day = 3, part = 2)]
+pub fn day3_part2_solution(input: &str) -> Result<Answer, AocError> {
+    let split = timing::parse(|| parse_input(input))?;
+    Ok(day3_part2(&split))
 }
 
 #[cfg(test)]
@@ -146,11 +165,14 @@ mod tests {
 
     #[test]
     fn test_day3() {
-        let ans = day3_part1(&vec![
+        let ans = day3_part1(&[
             "00100", "11110", "10110", "10111", "10101", "01111", "00111", "11100", "10000",
             "11001", "00010", "01010",
         ]);
         assert_eq!(ans, "198");
+
+        assert!(parse_input("00100\n1110").is_err());
+        assert!(parse_input("00100\n11120").is_err());
     }
 
     #[test]
@@ -1,4 +1,10 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::collections::HashMap;
+
+use common::{
+    aoc,
+    combinators::{self, integer, lines, pair, sections, separated, words},
+    timing, Answer, AocError, ParseError, Span,
+};
 
 #[derive(Hash, Eq, PartialEq, Debug)]
 struct BingoInfo {
@@ -61,7 +67,7 @@ impl BingoCard {
         won
     }
 
-    fn compute_minimum_win_rounds(&mut self, rounds: &Vec<u32>) {
+    fn compute_minimum_win_rounds(&mut self, rounds: &[u32]) {
         rounds.iter().enumerate().any(|(index, value)| {
             self.add(*value);
             let won = self.won();
@@ -76,81 +82,45 @@ impl BingoCard {
     fn compute_winning_product(&self, winning_number: u32) -> u32 {
         self.numbers_by_index
             .iter()
-            .map(
-                |(number, info)| {
-                    if info.visited == true {
-                        0
-                    } else {
-                        *number
-                    }
-                },
-            )
+            .map(|(number, info)| if info.visited { 0 } else { *number })
             .sum::<u32>()
             * winning_number
     }
 
     // PRIVATE
     fn check_won<'a>(&self, mut iter: impl Iterator<Item = &'a u32>) -> bool {
-        iter.all(|x| self.numbers_by_index[x].visited == true)
+        iter.all(|x| self.numbers_by_index[x].visited)
     }
 
     fn create_iter_at_index(&self, collect_i: usize) -> impl Iterator<Item = &u32> {
         assert!(collect_i <= 4);
         self.numbers
             .iter()
-            .flat_map(|v| v)
+            .flatten()
             .enumerate()
             .filter(move |(index, _)| index % 5 == collect_i)
             .map(|x| x.1)
     }
 }
 
-fn parse_values_from_file(path: &PathBuf) -> (Vec<u32>, Vec<BingoCard>) {
-    let read = common::read_file(path);
-    let split = read.trim().split("\n").collect::<Vec<&str>>();
-
-    // *
-    let numbers = split
-        .iter()
-        .nth(0)
-        .map(|x| x.trim())
-        .unwrap()
-        .split(',')
-        .map(|x| u32::from_str_radix(x, 10).unwrap())
-        .collect::<Vec<u32>>();
-    // println!("{:?}", numbers);
-
-    // *
-    let mut bingo_cards = Vec::new();
-
-    let mut iter = split.iter().skip(1);
-    let mut curr = iter
-        .by_ref()
-        .skip(1)
-        .take(5)
-        .map(|x| x.trim())
-        .collect::<Vec<&str>>();
-    while curr.len() == 5 {
-        let bingo_numbers = curr
-            .iter()
-            .map(|x| {
-                x.split_whitespace()
-                    .map(|y| u32::from_str_radix(y, 10).unwrap())
-                    .collect::<Vec<u32>>()
-            })
-            .collect::<Vec<Vec<u32>>>();
-        // println!("{:?}", bingo_numbers);
-        bingo_cards.push(BingoCard::new(bingo_numbers));
-
-        curr = iter
-            .by_ref()
-            .skip(1)
-            .take(5)
-            .map(|x| x.trim())
-            .collect::<Vec<&str>>();
+/// Parses a 5x5 bingo card
+fn parse_card(input: Span) -> Result<BingoCard, ParseError> {
+    let rows = lines(words(integer::<u32>))(input)?;
+    if rows.len() != 5 {
+        return Err(input.end().error("5 rows per bingo card"));
+    }
+    if let Some((row, _)) = input.lines().zip(&rows).find(|(_, r)| r.len() != 5) {
+        return Err(row.trim().error("5 numbers in a bingo row"));
     }
-    // println!("{:?}", bingo_cards.len());
-    (numbers, bingo_cards)
+    Ok(BingoCard::new(rows))
+}
+
+fn parse_input(input: &str) -> Result<(Vec<u32>, Vec<BingoCard>), ParseError> {
+    // NOTE, The drawn numbers and every card are separated by blank lines
+    combinators::parse(
+        input,
+        pair(separated(",", integer::<u32>), "\n\n", sections(parse_card)),
+    )
 }
 
 fn day4_part1(numbers: Vec<u32>, mut bingo_cards: Vec<BingoCard>) -> String {
@@ -164,19 +134,17 @@ fn day4_part1(numbers: Vec<u32>, mut bingo_cards: Vec<BingoCard>) -> String {
             }
             won
         });
-        match winning_value {
-            Some(_) => {
-                break;
-            }
-            None => {}
+        if winning_value.is_some() {
+            break;
         }
     }
     winning_value.unwrap().to_string()
 }
 
-pub fn day4_part1_solution(path: &PathBuf) -> String {
-    let (numbers, bingo_cards) = parse_values_from_file(path);
-    day4_part1(numbers, bingo_cards)
+#[aoc(year = 2021, day = 4, part = 1)]
+pub fn day4_part1_solution(input: &str) -> Result<Answer, AocError> {
+    let (numbers, bingo_cards) = timing::parse(|| parse_input(input))?;
+    Ok(day4_part1(numbers, bingo_cards))
 }
 
 fn day4_part2(numbers: Vec<u32>, mut bingo_cards: Vec<BingoCard>) -> String {
@@ -197,9 +165,10 @@ fn day4_part2(numbers: Vec<u32>, mut bingo_cards: Vec<BingoCard>) -> String {
         .to_string()
 }
 
-pub fn day4_part2_solution(path: &PathBuf) -> String {
-    let (numbers, bingo_cards) = parse_values_from_file(path);
-    day4_part2(numbers, bingo_cards)
+#[aoc(year = 2021, day = 4, part = 2)]
+pub fn day4_part2_solution(input: &str) -> Result<Answer, AocError> {
+    let (numbers, bingo_cards) = timing::parse(|| parse_input(input))?;
+    Ok(day4_part2(numbers, bingo_cards))
 }
 
 #[cfg(test)]
@@ -225,16 +194,12 @@ mod tests {
         assert_eq!(card.numbers_by_index[&6].index, (3, 0));
 
         assert_eq!(
-            card.create_iter_at_index(0)
-                .map(|x| *x)
-                .collect::<Vec<u32>>(),
+            card.create_iter_at_index(0).copied().collect::<Vec<u32>>(),
             vec![22, 8, 21, 6, 1]
         );
 
         assert_eq!(
-            card.create_iter_at_index(2)
-                .map(|x| *x)
-                .collect::<Vec<u32>>(),
+            card.create_iter_at_index(2).copied().collect::<Vec<u32>>(),
             vec![17, 23, 14, 3, 20]
         );
     }
@@ -1,101 +1,45 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::collections::HashMap;
+
+use common::{
+    aoc,
+    combinators::{self, integer, lines, map, pair},
+    timing, Answer, AocError, ParseError, Point,
+};
 
 #[derive(Debug)]
 struct Range {
-    p1: (u32, u32),
-    p2: (u32, u32),
+    p1: Point,
+    p2: Point,
 }
 
 impl Range {
-    fn new(p1: (u32, u32), p2: (u32, u32)) -> Self {
+    fn new(p1: Point, p2: Point) -> Self {
         Range { p1, p2 }
     }
 
     fn line_is_vertical(&self) -> bool {
-        if self.p1.0 == self.p2.0 {
-            true
-        } else {
-            false
-        }
+        self.p1.x == self.p2.x
     }
 
     fn line_is_horizontal(&self) -> bool {
-        if self.p1.1 == self.p2.1 {
-            true
-        } else {
-            false
-        }
-    }
-
-    fn get_list(&self) -> Vec<(u32, u32)> {
-        let mut rvec = vec![self.p1];
-        let mut next_num = self.next_number(self.p1);
-        while next_num != None {
-            let current_num = next_num.unwrap();
-            rvec.push(current_num);
-            next_num = self.next_number(current_num);
-        }
-        rvec
+        self.p1.y == self.p2.y
     }
 
-    // PRIVATE
-    fn next_number(&self, from: (u32, u32)) -> Option<(u32, u32)> {
-        let x = from.0;
-        let y = from.1;
-
-        let to_x = self.p2.0;
-        let to_y = self.p2.1;
-
-        let mut opt = None;
-        if from != self.p2 {
-            let new_x: u32;
-            let new_y: u32;
-            if x == to_x {
-                new_x = x;
-            } else if x > to_x {
-                new_x = x - 1;
-            } else {
-                // x < to_x
-                new_x = x + 1;
-            }
-
-            if y == to_y {
-                new_y = y;
-            } else if y > to_y {
-                new_y = y - 1;
-            } else {
-                // y < to_y
-                new_y = y + 1;
-            }
-            opt = Some((new_x, new_y))
-        }
-        opt
+    // NOTE, Lines are horizontal, vertical or at 45°
+    fn get_list(&self) -> Vec<Point> {
+        self.p1.line_to(self.p2).collect()
     }
 }
 
-fn parse_values_from_file(path: &PathBuf) -> Vec<Range> {
-    let data = common::read_file(path);
-    data.trim()
-        .split('\n')
-        .map(|m| m.trim().split("->").collect::<Vec<&str>>())
-        .map(|x| {
-            let p1 = x[0]
-                .trim()
-                .split(',')
-                .map(|z| u32::from_str_radix(z, 10).unwrap())
-                .collect::<Vec<u32>>();
-            let p2 = x[1]
-                .trim()
-                .split(',')
-                .map(|z| u32::from_str_radix(z, 10).unwrap())
-                .collect::<Vec<u32>>();
-            Range::new((p1[0], p1[1]), (p2[0], p2[1]))
-        })
-        .collect::<Vec<Range>>()
+fn parse_input(input: &str) -> Result<Vec<Range>, ParseError> {
+    // NOTE, One `x,y -> x,y` segment per line
+    let point = || map(pair(integer::<usize>, ",", integer::<usize>), Point::from);
+    let segment = map(pair(point(), "->", point()), |(p1, p2)| Range::new(p1, p2));
+    combinators::parse(input, lines(segment))
 }
 
-fn day5_part1(ranges: &Vec<Range>) -> String {
-    let mut hashmap: HashMap<(u32, u32), u32> = HashMap::new();
+fn day5_part1(ranges: &[Range]) -> String {
+    let mut hashmap: HashMap<Point, u32> = HashMap::new();
 
     ranges
         .iter()
@@ -112,8 +56,8 @@ fn day5_part1(ranges: &Vec<Range>) -> String {
         });
 
     hashmap
-        .iter()
-        .map(|(_key, value)| {
+        .values()
+        .map(|value| {
             // println!("{:?} -> {}", _key, value);
             if *value > 1 {
                 1
@@ -125,13 +69,14 @@ fn day5_part1(ranges: &Vec<Range>) -> String {
         .to_string()
 }
 
-pub fn day5_part1_solution(path: &PathBuf) -> String {
-    let ranges = parse_values_from_file(path);
-    day5_part1(&ranges)
+#[aoc(year = 2021, day = 5, part = 1)]
+pub fn day5_part1_solution(input: &str) -> Result<Answer, AocError> {
+    let ranges = timing::parse(|| parse_input(input))?;
+    Ok(day5_part1(&ranges))
 }
 
-fn day5_part2(ranges: &Vec<Range>) -> String {
-    let mut hashmap: HashMap<(u32, u32), u32> = HashMap::new();
+fn day5_part2(ranges: &[Range]) -> String {
+    let mut hashmap: HashMap<Point, u32> = HashMap::new();
 
     ranges.iter().for_each(|x| {
         x.get_list().iter().for_each(|y| match hashmap.get_mut(y) {
@@ -145,8 +90,8 @@ fn day5_part2(ranges: &Vec<Range>) -> String {
     });
 
     hashmap
-        .iter()
-        .map(|(_key, value)| {
+        .values()
+        .map(|value| {
             // println!("{:?} -> {}", _key, value);
             if *value > 1 {
                 1
@@ -158,9 +103,10 @@ fn day5_part2(ranges: &Vec<Range>) -> String {
         .to_string()
 }
 
-pub fn day5_part2_solution(path: &PathBuf) -> String {
-    let ranges = parse_values_from_file(path);
-    day5_part2(&ranges)
+#[aoc(year = 2021, day = 5, part = 2)]
+pub fn day5_part2_solution(input: &str) -> Result<Answer, AocError> {
+    let ranges = timing::parse(|| parse_input(input))?;
+    Ok(day5_part2(&ranges))
 }
 
 #[cfg(test)]
@@ -168,7 +114,7 @@ mod tests {
     use super::*;
 
     #[test]
-    fn parse_input() {
+    fn test_parse_input() {
         let data = "0,9 -> 5,9
 8,0 -> 0,8
 9,4 -> 3,4
@@ -179,40 +125,38 @@ mod tests {
 3,4 -> 1,4
 0,0 -> 8,8
 5,5 -> 8,2
-        "
-        .trim();
-        let _split = data
-            .split('\n')
-            .map(|m| m.trim().split("->").collect::<Vec<&str>>())
-            .map(|x| {
-                let p1 = x[0]
-                    .trim()
-                    .split(',')
-                    .map(|z| u32::from_str_radix(z, 10).unwrap())
-                    .collect::<Vec<u32>>();
-                let p2 = x[1]
-                    .trim()
-                    .split(',')
-                    .map(|z| u32::from_str_radix(z, 10).unwrap())
-                    .collect::<Vec<u32>>();
-                Range::new((p1[0], p1[1]), (p2[0], p2[1]))
-            })
-            .collect::<Vec<Range>>();
-        // println!("{:?}", split);
+        ";
+        let ranges = super::parse_input(data).unwrap();
+        assert_eq!(ranges.len(), 10);
+        assert_eq!(
+            (ranges[1].p1, ranges[1].p2),
+            (Point::new(8, 0), Point::new(0, 8))
+        );
+
+        let error = super::parse_input("0,9 -> 5,9\n8,0 0,8").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 1));
+        assert_eq!(error.expected, "`->`");
+        assert_eq!(error.text, "8,0 0,8");
+
+        let error = super::parse_input("0,9 -> 5").unwrap_err();
+        assert_eq!(
+            (error.line, error.column, error.expected.as_str()),
+            (1, 8, "`,`")
+        );
     }
 
     #[test]
     fn range_get_list() {
-        let range = Range::new((0, 9), (5, 9));
+        let range = Range::new(Point::new(0, 9), Point::new(5, 9));
         assert_eq!(
             range.get_list(),
-            vec![(0, 9), (1, 9), (2, 9), (3, 9), (4, 9), (5, 9),]
+            [(0, 9), (1, 9), (2, 9), (3, 9), (4, 9), (5, 9)].map(Point::from)
         );
 
-        let range = Range::new((8, 0), (0, 8));
+        let range = Range::new(Point::new(8, 0), Point::new(0, 8));
         assert_eq!(
             range.get_list(),
-            vec![
+            [
                 (8, 0),
                 (7, 1),
                 (6, 2),
@@ -223,28 +167,29 @@ mod tests {
                 (1, 7),
                 (0, 8),
             ]
+            .map(Point::from)
         );
 
-        let range = Range::new((2, 2), (2, 0));
-        assert_eq!(range.get_list(), vec![(2, 2), (2, 1), (2, 0),]);
+        let range = Range::new(Point::new(2, 2), Point::new(2, 0));
+        assert_eq!(range.get_list(), [(2, 2), (2, 1), (2, 0)].map(Point::from));
     }
 
     #[test]
     fn range_itersection() {
         let ranges = vec![
-            Range::new((0, 9), (5, 9)),
-            Range::new((8, 0), (0, 8)),
-            Range::new((9, 4), (3, 4)),
-            Range::new((2, 2), (2, 1)),
-            Range::new((7, 0), (7, 4)),
-            Range::new((6, 4), (2, 0)),
-            Range::new((0, 9), (2, 9)),
-            Range::new((3, 4), (1, 4)),
-            Range::new((0, 0), (8, 8)),
-            Range::new((5, 5), (8, 2)),
+            Range::new(Point::new(0, 9), Point::new(5, 9)),
+            Range::new(Point::new(8, 0), Point::new(0, 8)),
+            Range::new(Point::new(9, 4), Point::new(3, 4)),
+            Range::new(Point::new(2, 2), Point::new(2, 1)),
+            Range::new(Point::new(7, 0), Point::new(7, 4)),
+            Range::new(Point::new(6, 4), Point::new(2, 0)),
+            Range::new(Point::new(0, 9), Point::new(2, 9)),
+            Range::new(Point::new(3, 4), Point::new(1, 4)),
+            Range::new(Point::new(0, 0), Point::new(8, 8)),
+            Range::new(Point::new(5, 5), Point::new(8, 2)),
         ];
 
-        let mut hashmap: HashMap<(u32, u32), u32> = HashMap::new();
+        let mut hashmap: HashMap<Point, u32> = HashMap::new();
 
         ranges.iter().for_each(|x| {
             x.get_list().iter().for_each(|y| match hashmap.get_mut(y) {
@@ -257,11 +202,11 @@ mod tests {
             })
         });
 
-        assert_eq!(hashmap.get(&(0, 9)), Some(&2));
-        assert_eq!(hashmap.get(&(1, 9)), Some(&2));
-        assert_eq!(hashmap.get(&(2, 9)), Some(&2));
-        assert_eq!(hashmap.get(&(3, 4)), Some(&2));
-        assert_eq!(hashmap.get(&(7, 4)), Some(&2));
+        assert_eq!(hashmap.get(&Point::new(0, 9)), Some(&2));
+        assert_eq!(hashmap.get(&Point::new(1, 9)), Some(&2));
+        assert_eq!(hashmap.get(&Point::new(2, 9)), Some(&2));
+        assert_eq!(hashmap.get(&Point::new(3, 4)), Some(&2));
+        assert_eq!(hashmap.get(&Point::new(7, 4)), Some(&2));
 
         assert_eq!(day5_part1(&ranges), "5");
         assert_eq!(day5_part2(&ranges), "12");
@@ -1,4 +1,9 @@
-use std::{collections::HashMap, path::PathBuf};
+use common::{
+    aoc,
+    combinators::{self, separated},
+    timing, Answer, AocError, ParseError, Simulation, Span,
+};
+use num_bigint::BigUint;
 
 struct FishState {
     current_state: Vec<u32>,
@@ -12,10 +17,10 @@ impl FishState {
     }
 }
 
-impl Iterator for FishState {
-    type Item = Vec<u32>;
+impl Simulation for FishState {
+    type State = Vec<u32>;
 
-    fn next(&mut self) -> Option<Self::Item> {
+    fn step(&mut self) -> bool {
         let mut add_more: Vec<u32> = Vec::new();
         self.current_state.iter_mut().for_each(|x| {
             if *x == 0 {
@@ -27,85 +32,234 @@ impl Iterator for FishState {
         });
 
         self.current_state.append(&mut add_more);
-        Some(self.current_state.clone())
+        true
     }
-}
-
-fn simulate_days(initial_state: u32, remaining_days: usize) -> Vec<u32> {
-    FishState::new(vec![initial_state])
-        .nth(remaining_days)
-        .unwrap()
-}
 
-fn day6_part1(state: Vec<u32>) -> String {
-    FishState::new(state).nth(79).unwrap().len().to_string()
+    fn state(&self) -> Self::State {
+        self.current_state.clone()
+    }
 }
 
-pub fn day6_part1_solution(path: &PathBuf) -> String {
-    let data = common::read_file(path);
-    let state = data
-        .trim()
-        .split(',')
-        .map(|x| u32::from_str_radix(x, 10).unwrap())
-        .collect::<Vec<u32>>();
-    day6_part1(state)
+/// The fishes after `days` days
+fn simulate(state: Vec<u32>, days: usize) -> Vec<u32> {
+    let mut fish_state = FishState::new(state);
+    fish_state.run(days);
+    fish_state.current_state
 }
 
-fn day6_part2(state: Vec<u32>) -> String {
-    let hashmap = HashMap::from([
-        (0, simulate_days(0, 127)),
-        (1, simulate_days(1, 127)),
-        (2, simulate_days(2, 127)),
-        (3, simulate_days(3, 127)),
-        (4, simulate_days(4, 127)),
-        (5, simulate_days(5, 127)),
-        (6, simulate_days(6, 127)),
-        (7, simulate_days(7, 127)),
-        (8, simulate_days(8, 127)),
-    ]);
+/// The number of fishes after `days` days, without simulating all of them
+///
+/// Every fish only depends on its own timer, so a single fish of each timer
+/// is simulated for half of the days and the fishes it became are looked up
+/// in the counts for the other half
+fn count_memoized(state: &[u32], days: usize) -> usize {
+    let half = days / 2;
+    let fishes = (0..TIMERS as u32)
+        .map(|timer| simulate(vec![timer], half))
+        .collect::<Vec<_>>();
+    let counts = (0..TIMERS as u32)
+        .map(|timer| simulate(vec![timer], days - half).len())
+        .collect::<Vec<_>>();
 
     // Example [3, 4, 3, 1, 2] -> [ 3 -> expanded 128 times]
     // [3 ... 127 times] take each time [expand another 128 times]
     state
         .iter()
-        .map(|x| {
-            hashmap
-                .get(x)
-                .unwrap()
+        .map(|&x| {
+            fishes[x as usize]
                 .iter()
-                .map(|y| hashmap.get(y).unwrap().len())
+                .map(|&y| counts[y as usize])
                 .sum::<usize>()
         })
-        .sum::<usize>()
-        .to_string()
+        .sum()
+}
+
+/// Timers go from 0 to 8
+const TIMERS: usize = 9;
+
+type Matrix = [[BigUint; TIMERS]; TIMERS];
+
+fn identity() -> Matrix {
+    std::array::from_fn(|i| std::array::from_fn(|j| BigUint::from(u8::from(i == j))))
+}
+
+/// How many fishes of each timer there are after one day, per fish of each
+/// timer the day before
+fn one_day() -> Matrix {
+    let mut matrix = Matrix::default();
+    for timer in 1..TIMERS {
+        matrix[timer][timer - 1] = BigUint::from(1u8);
+    }
+    // NOTE, A fish at 0 restarts at 6 and gives birth to a fish at 8
+    matrix[0][6] = BigUint::from(1u8);
+    matrix[0][8] = BigUint::from(1u8);
+    matrix
+}
+
+/// `a * b`, every value taken modulo `modulus` if there is one
+fn multiply(a: &Matrix, b: &Matrix, modulus: Option<&BigUint>) -> Matrix {
+    std::array::from_fn(|i| {
+        std::array::from_fn(|j| {
+            let value = (0..TIMERS).map(|k| &a[i][k] * &b[k][j]).sum::<BigUint>();
+            match modulus {
+                Some(modulus) => value % modulus,
+                None => value,
+            }
+        })
+    })
+}
+
+/// `matrix` to the power `n` by repeated squaring
+fn power(matrix: &Matrix, mut n: u64, modulus: Option<&BigUint>) -> Matrix {
+    let mut result = identity();
+    let mut square = matrix.clone();
+    while n > 0 {
+        if n & 1 == 1 {
+            result = multiply(&result, &square, modulus);
+        }
+        n >>= 1;
+        if n > 0 {
+            square = multiply(&square, &square, modulus);
+        }
+    }
+    result
+}
+
+/// The fishes counted by timer, all fishes with the same timer behave the
+/// same so there is no need to tell them apart
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FishCounts {
+    counts: [BigUint; TIMERS],
+}
+
+impl FishCounts {
+    fn new(state: &[u32]) -> Self {
+        let mut counts = <[BigUint; TIMERS]>::default();
+        for &timer in state {
+            counts[timer as usize] += 1u8;
+        }
+        Self { counts }
+    }
+
+    /// The counts `days` days later, in O(log days) matrix products, taken
+    /// modulo `modulus` if there is one
+    ///
+    /// The population grows by about 9% a day, 10,000 days already take
+    /// hundreds of digits. A modulus keeps the numbers small for any number
+    /// of days, 10^18 days can't be counted exactly
+    fn after(&self, days: u64, modulus: Option<u64>) -> Self {
+        let modulus = modulus.map(BigUint::from);
+        let modulus = modulus.as_ref();
+        let matrix = power(&one_day(), days, modulus);
+        let counts = std::array::from_fn(|j| {
+            let count = (0..TIMERS)
+                .map(|i| &self.counts[i] * &matrix[i][j])
+                .sum::<BigUint>();
+            match modulus {
+                Some(modulus) => count % modulus,
+                None => count,
+            }
+        });
+        Self { counts }
+    }
+
+    fn total(&self) -> BigUint {
+        self.counts.iter().sum()
+    }
+}
+
+impl Simulation for FishCounts {
+    type State = [BigUint; TIMERS];
+
+    fn step(&mut self) -> bool {
+        self.counts.rotate_left(1);
+        let born = self.counts[8].clone();
+        self.counts[6] += born;
+        true
+    }
+
+    fn state(&self) -> Self::State {
+        self.counts.clone()
+    }
+}
+
+/// The number of fishes after `days` days, modulo `modulus` if there is one,
+/// see [`FishCounts::after`]
+fn count_fishes(state: &[u32], days: u64, modulus: Option<u64>) -> BigUint {
+    let total = FishCounts::new(state).after(days, modulus).total();
+    match modulus {
+        Some(modulus) => total % modulus,
+        None => total,
+    }
+}
+
+fn day6_part1(state: Vec<u32>) -> String {
+    simulate(state, 80).len().to_string()
 }
 
-pub fn day6_part2_solution(path: &PathBuf) -> String {
-    let data = common::read_file(path);
-    let state = data
-        .trim()
-        .split(',')
-        .map(|x| u32::from_str_radix(x, 10).unwrap())
-        .collect::<Vec<u32>>();
-    day6_part2(state)
+fn parse_timer(timer: Span) -> Result<u32, ParseError> {
+    match timer.parse::<u32>()? {
+        timer @ 0..=8 => Ok(timer),
+        _ => Err(timer.error("a timer between 0 and 8")),
+    }
+}
+
+fn parse_input(input: &str) -> Result<Vec<u32>, ParseError> {
+    combinators::parse(input, separated(",", parse_timer))
+}
+
+#[aoc(year = 2021, day = 6, part = 1)]
+pub fn day6_part1_solution(input: &str) -> Result<Answer, AocError> {
+    let state = timing::parse(|| parse_input(input))?;
+    Ok(day6_part1(state))
+}
+
+#[aoc(year = 2021, day = 6, part = 1, variant = "memoized")]
+pub fn day6_part1_memoized_solution(input: &str) -> Result<Answer, AocError> {
+    let state = timing::parse(|| parse_input(input))?;
+    Ok(count_memoized(&state, 80).to_string())
+}
+
+#[aoc(year = 2021, day = 6, part = 1, variant = "matrix")]
+pub fn day6_part1_matrix_solution(input: &str) -> Result<Answer, AocError> {
+    let state = timing::parse(|| parse_input(input))?;
+    Ok(count_fishes(&state, 80, None).to_string())
+}
+
+fn day6_part2(state: Vec<u32>) -> String {
+    count_fishes(&state, 256, None).to_string()
+}
+
+#[aoc(year = 2021, day = 6, part = 2)]
+pub fn day6_part2_solution(input: &str) -> Result<Answer, AocError> {
+    let state = timing::parse(|| parse_input(input))?;
+    Ok(day6_part2(state))
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+
+    use proptest::prelude::*;
+
     use super::*;
 
+    fn simulate_days(initial_state: u32, remaining_days: usize) -> Vec<u32> {
+        simulate(vec![initial_state], remaining_days + 1)
+    }
+
     #[test]
     fn simulate_aoc_base() {
         let state = "3,4,3,1,2";
         let fishes = state
             .trim()
             .split(',')
-            .map(|x| u32::from_str_radix(x, 10).unwrap())
+            .map(|x| x.parse::<u32>().unwrap())
             .collect::<Vec<u32>>();
         assert_eq!(fishes, vec![3, 4, 3, 1, 2]);
 
-        assert_eq!(FishState::new(fishes.clone()).nth(17).unwrap().len(), 26);
-        assert_eq!(FishState::new(fishes.clone()).nth(79).unwrap().len(), 5934);
+        assert_eq!(simulate(fishes.clone(), 18).len(), 26);
+        assert_eq!(simulate(fishes.clone(), 80).len(), 5934);
 
         let hashmap = HashMap::from([
             (0, simulate_days(0, 127).len()),
@@ -119,9 +273,7 @@ mod tests {
             (8, simulate_days(8, 127).len()),
         ]);
 
-        let ans = FishState::new(fishes.clone())
-            .nth(127)
-            .unwrap()
+        let ans = simulate(fishes.clone(), 128)
             .iter()
             .map(|x| *hashmap.get(x).unwrap())
             .sum::<usize>();
@@ -134,10 +286,69 @@ mod tests {
         let fishes = state
             .trim()
             .split(',')
-            .map(|x| u32::from_str_radix(x, 10).unwrap())
+            .map(|x| x.parse::<u32>().unwrap())
             .collect::<Vec<u32>>();
         assert_eq!(fishes, vec![3, 4, 3, 1, 2]);
 
         assert_eq!(day6_part2(fishes), "26984457539");
     }
+
+    #[test]
+    fn test_count_memoized() {
+        assert_eq!(count_memoized(&[3, 4, 3, 1, 2], 18), 26);
+        assert_eq!(count_memoized(&[3, 4, 3, 1, 2], 80), 5934);
+    }
+
+    #[test]
+    fn test_count_fishes() {
+        let fishes = [3, 4, 3, 1, 2];
+        assert_eq!(count_fishes(&fishes, 18, None), BigUint::from(26u8));
+        assert_eq!(
+            count_fishes(&fishes, 256, None),
+            BigUint::from(26984457539u64)
+        );
+        for timer in 0..=8 {
+            for days in 0..100 {
+                let expected = BigUint::from(simulate(vec![timer], days).len());
+                assert_eq!(count_fishes(&[timer], days as u64, None), expected);
+            }
+        }
+
+        // NOTE, Jumping ahead gives the same counts as stepping day by day
+        let mut counts = FishCounts::new(&fishes);
+        counts.run(10_000);
+        assert_eq!(FishCounts::new(&fishes).after(10_000, None), counts);
+        let total = count_fishes(&fishes, 10_000, None).to_string();
+        assert_eq!(total.len(), 380);
+        assert!(total.starts_with("12295220913725202160"));
+        assert!(total.ends_with("15511445328619725056"));
+    }
+
+    #[test]
+    fn test_count_fishes_modulo() {
+        let fishes = [3, 4, 3, 1, 2];
+        let modulus = 1_000_000_007;
+        let exact = count_fishes(&fishes, 10_000, None) % modulus;
+        assert_eq!(exact, BigUint::from(356196848u32));
+        assert_eq!(count_fishes(&fishes, 10_000, Some(modulus)), exact);
+        assert_eq!(
+            count_fishes(&fishes, 1_000_000_000_000_000_000, Some(modulus)),
+            BigUint::from(860170227u32)
+        );
+        assert_eq!(count_fishes(&fishes, 256, Some(1)), BigUint::ZERO);
+    }
+
+    #[test]
+    fn test_parse_timers() {
+        assert_eq!(parse_input("3,4,3,1,2"), Ok(vec![3, 4, 3, 1, 2]));
+        assert!(parse_input("3,9,3").is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn test_day6_variants(timers in prop::collection::vec(0..=8u32, 1..12)) {
+            let input = timers.iter().map(u32::to_string).collect::<Vec<_>>().join(",");
+            common::assert_implementations_agree(2021, 6, &input);
+        }
+    }
 }
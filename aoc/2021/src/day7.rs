@@ -1,4 +1,8 @@
-use std::path::PathBuf;
+use common::{
+    aoc,
+    combinators::{self, integer, separated},
+    timing, Answer, AocError, ParseError,
+};
 
 fn get_max(data: &[u32]) -> u32 {
     *data.iter().max().unwrap()
@@ -23,7 +27,7 @@ fn day7_part1(data: &[u32]) -> u32 {
 
     // Compute the mid value (+1 and -1 value)
     let mut final_answer = 0;
-    while low <= high {
+    while current_low <= current_high {
         let abs_mid = compute_fuel_usage(data, current_mid);
         let abs_low = compute_fuel_usage(data, current_mid - 1);
         let abs_high = compute_fuel_usage(data, current_mid + 1);
@@ -45,15 +49,14 @@ fn day7_part1(data: &[u32]) -> u32 {
     final_answer
 }
 
-pub fn day7_part1_solution(path: &PathBuf) -> String {
-    let data = common::read_file(path);
+fn parse_input(input: &str) -> Result<Vec<u32>, ParseError> {
+    combinators::parse(input, separated(",", integer::<u32>))
+}
 
-    let parsed_data = data
-        .trim()
-        .split(',')
-        .map(|x| u32::from_str_radix(x, 10).unwrap())
-        .collect::<Vec<u32>>();
-    day7_part1(&parsed_data).to_string()
+#[aoc(year = 2021, day = 7, part = 1)]
+pub fn day7_part1_solution(input: &str) -> Result<Answer, AocError> {
+    let parsed_data = timing::parse(|| parse_input(input))?;
+    Ok(day7_part1(&parsed_data).to_string())
 }
 
 fn compute_fuel_usage_increased_burn(data: &[u32], pos: u32) -> u32 {
@@ -76,7 +79,7 @@ fn day7_part2(data: &[u32]) -> u32 {
 
     // Compute the mid value (+1 and -1 value)
     let mut final_answer = 0;
-    while low <= high {
+    while current_low <= current_high {
         let abs_mid = compute_fuel_usage_increased_burn(data, current_mid);
         let abs_low = compute_fuel_usage_increased_burn(data, current_mid - 1);
         let abs_high = compute_fuel_usage_increased_burn(data, current_mid + 1);
@@ -98,15 +101,10 @@ fn day7_part2(data: &[u32]) -> u32 {
     final_answer
 }
 
-pub fn day7_part2_solution(path: &PathBuf) -> String {
-    let data = common::read_file(path);
-
-    let parsed_data = data
-        .trim()
-        .split(',')
-        .map(|x| u32::from_str_radix(x, 10).unwrap())
-        .collect::<Vec<u32>>();
-    day7_part2(&parsed_data).to_string()
+#[aoc(year = 2021, day = 7, part = 2)]
+pub fn day7_part2_solution(input: &str) -> Result<Answer, AocError> {
+    let parsed_data = timing::parse(|| parse_input(input))?;
+    Ok(day7_part2(&parsed_data).to_string())
 }
 
 #[cfg(test)]
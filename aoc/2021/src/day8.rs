@@ -1,4 +1,11 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::collections::HashMap;
+
+use common::{aoc, csp::Problem, timing, Answer, AocError, ParseError, Span};
+
+/// The segments lit by each digit
+const DIGITS: [&str; 10] = [
+    "abcefg", "cf", "acdeg", "acdfg", "bcdf", "abdfg", "abdefg", "acf", "abcdefg", "abcdfg",
+];
 
 fn sort(data: &str) -> String {
     let mut char_iter = String::from(data).chars().collect::<Vec<char>>();
@@ -11,7 +18,7 @@ struct Pattern {
     output_value: Vec<String>,
 }
 
-impl<'a> Pattern {
+impl Pattern {
     fn new(data: &str) -> Self {
         let parsed_data = data
             .trim()
@@ -19,14 +26,8 @@ impl<'a> Pattern {
             .map(|x| x.trim())
             .collect::<Vec<&str>>();
 
-        let unique_pattern = parsed_data[0]
-            .split(' ')
-            .map(|x| sort(x))
-            .collect::<Vec<String>>();
-        let output_value = parsed_data[1]
-            .split(' ')
-            .map(|x| sort(x))
-            .collect::<Vec<String>>();
+        let unique_pattern = parsed_data[0].split(' ').map(sort).collect::<Vec<String>>();
+        let output_value = parsed_data[1].split(' ').map(sort).collect::<Vec<String>>();
         Pattern {
             unique_pattern,
             output_value,
@@ -48,141 +49,78 @@ impl<'a> Pattern {
         }
     }
 
-    fn deduce_output_value(&self) -> u32 {
-        let numbers = self.deduce_numbers_from_unique_pattern();
-
-        self.output_value
-            .iter()
-            .map(|x| {
-                let s = sort(x);
-                numbers.get(&s).unwrap()
-            })
-            .fold(0_u32, |acc, &elem| acc * 10 + elem as u32)
-    }
-
-    fn deduce_numbers_from_unique_pattern(&self) -> HashMap<&String, u8> {
-        let mut hashmap_by_strkey = HashMap::new();
-        let mut hashmap_by_numkey = HashMap::new();
-        self.unique_pattern.iter().for_each(|x| {
-            let value = Pattern::get_unique_number_from_str(x);
-            match value {
-                Some(v) => {
-                    hashmap_by_strkey.insert(x, v);
-                    hashmap_by_numkey.insert(v, x);
-                }
-                None => {}
+    fn deduce_output_value(&self) -> Result<u32, AocError> {
+        let numbers = self.deduce_numbers_from_unique_pattern()?;
+
+        self.output_value.iter().try_fold(0_u32, |acc, x| {
+            let s = sort(x);
+            match numbers.get(&s) {
+                Some(&digit) => Ok(acc * 10 + digit as u32),
+                None => Err(AocError::Unsolvable(format!(
+                    "output `{}` matches none of the patterns",
+                    x
+                ))),
             }
-        });
+        })
+    }
 
-        let str1 = *hashmap_by_numkey.get(&1).unwrap();
-        let str4 = *hashmap_by_numkey.get(&4).unwrap();
-        // 6 len: 0, 6, 9
-        // 5 len: 2, 3, 5
+    /// Every pattern lights as many segments as its digit has, and two
+    /// patterns share as many wires as their digits share segments
+    fn deduce_numbers_from_unique_pattern(&self) -> Result<HashMap<&String, u8>, AocError> {
+        let shared = |a: &str, b: &str| a.chars().filter(|&c| b.contains(c)).count();
 
-        // Get 3, filter by len 5, remove all 1's overlap -> 3 len (3)
-        // Get 2, filter by len 5, remove all 4's overlap -> 3 len (3)
-        // Get 5 (remaining is 5)
-        let get_all_len5 = self
+        let mut problem = Problem::new();
+        let digits = self
             .unique_pattern
             .iter()
-            .filter(|x| x.len() == 5)
-            .collect::<Vec<&String>>();
+            .map(|_| problem.variable(0..DIGITS.len()))
+            .collect::<Vec<_>>();
+        problem.all_different(digits.iter().copied());
+        for (i, pattern) in self.unique_pattern.iter().enumerate() {
+            problem.constraint([digits[i]], move |d| DIGITS[*d[0]].len() == pattern.len());
+            for (j, other) in self.unique_pattern.iter().enumerate().skip(i + 1) {
+                let wires = shared(pattern, other);
+                problem.constraint([digits[i], digits[j]], move |d| {
+                    shared(DIGITS[*d[0]], DIGITS[*d[1]]) == wires
+                });
+            }
+        }
 
-        let str3 = get_all_len5
-            .iter()
-            .filter(|x| {
-                x.chars()
-                    .filter(|y| !str1.contains(*y))
-                    .collect::<Vec<char>>()
-                    .len()
-                    == 3
-            })
-            .map(|x| *x)
-            .reduce(|_, y| y)
-            .unwrap();
-        hashmap_by_strkey.insert(str3, 3);
-
-        let str2 = get_all_len5
-            .iter()
-            .filter(|x| {
-                x.chars()
-                    .filter(|y| !str4.contains(*y))
-                    .collect::<Vec<char>>()
-                    .len()
-                    == 3
-            })
-            .map(|x| *x)
-            .reduce(|_, y| y)
-            .unwrap();
-        hashmap_by_strkey.insert(str2, 2);
-
-        let str5 = get_all_len5
-            .iter()
-            .filter(|&&y| y != str2 && y != str3)
-            .map(|x| *x)
-            .reduce(|_, y| y)
-            .unwrap();
-        hashmap_by_strkey.insert(str5, 5);
-
-        // Get 6, filter by len 6, remove all 1's overlap -> 5 len
-        // Get 9, filter by len 6, remove all 4's overlap -> 2 len
-        // Get 0 (remaining is 0)
-        let get_all_len6 = self
+        // NOTE, The puzzle promises every display has exactly one wiring
+        let solution = problem.solve().ok_or_else(|| {
+            AocError::Unsolvable(format!(
+                "no wiring shows every digit for `{}`",
+                self.unique_pattern.join(" ")
+            ))
+        })?;
+        Ok(self
             .unique_pattern
             .iter()
-            .filter(|x| x.len() == 6)
-            .collect::<Vec<&String>>();
-
-        let str6 = get_all_len6
-            .iter()
-            .filter(|x| {
-                x.chars()
-                    .filter(|y| !str1.contains(*y))
-                    .collect::<Vec<char>>()
-                    .len()
-                    == 5
-            })
-            .map(|x| *x)
-            .reduce(|_, y| y)
-            .unwrap();
-        hashmap_by_strkey.insert(str6, 6);
-
-        let str9 = get_all_len6
-            .iter()
-            .filter(|x| {
-                x.chars()
-                    .filter(|y| !str4.contains(*y))
-                    .collect::<Vec<char>>()
-                    .len()
-                    == 2
-            })
-            .map(|x| *x)
-            .reduce(|_, y| y)
-            .unwrap();
-        hashmap_by_strkey.insert(str9, 9);
-
-        let str0 = get_all_len6
-            .iter()
-            .filter(|&&x| x != str6 && x != str9)
-            .map(|x| *x)
-            .reduce(|_, y| y)
-            .unwrap();
-        hashmap_by_strkey.insert(str0, 0);
-
-        hashmap_by_strkey
+            .zip(digits)
+            .map(|(pattern, digit)| (pattern, solution[digit] as u8))
+            .collect())
     }
 }
 
-fn parse_values_from_file(path: &PathBuf) -> Vec<Pattern> {
-    let data = common::read_file(path);
-
-    data.trim()
-        .split("\n")
-        .map(|x| Pattern::new(x))
-        .collect::<Vec<Pattern>>()
+fn parse_input(input: &str) -> Result<Vec<Pattern>, ParseError> {
+    Span::new(input)
+        .trim()
+        .lines()
+        .map(|line| {
+            // NOTE, 10 unique patterns and a 4 digit output value
+            let (patterns, output) = line.split_once("|")?;
+            if patterns.split_whitespace().count() != 10 {
+                return Err(patterns.trim().error("10 unique patterns"));
+            }
+            if output.split_whitespace().count() != 4 {
+                return Err(output.trim().error("4 output digits"));
+            }
+            Ok(Pattern::new(line.as_str()))
+        })
+        .collect()
 }
 
-fn day8_part1(data: &Vec<Pattern>) -> u32 {
+fn day8_part1(data: &[Pattern]) -> u32 {
     data.iter()
         .map(|x| {
             x.output_value
@@ -199,18 +137,20 @@ fn day8_part1(data: &Vec<Pattern>) -> u32 {
         .sum::<u32>()
 }
 
-pub fn day8_part1_solution(path: &PathBuf) -> String {
-    let parsed_data = parse_values_from_file(path);
-    day8_part1(&parsed_data).to_string()
+#[aoc(year = 2021, day = 8, part = 1)]
+pub fn day8_part1_solution(input: &str) -> Result<Answer, AocError> {
+    let parsed_data = timing::parse(|| parse_input(input))?;
+    Ok(day8_part1(&parsed_data).to_string())
 }
 
-fn day8_part2(data: &Vec<Pattern>) -> u32 {
+fn day8_part2(data: &[Pattern]) -> Result<u32, AocError> {
     data.iter().map(|x| x.deduce_output_value()).sum()
 }
 
-pub fn day8_part2_solution(path: &PathBuf) -> String {
-    let parsed_data = parse_values_from_file(path);
-    day8_part2(&parsed_data).to_string()
+#[aoc(year = 2021, day = 8, part = 2)]
+pub fn day8_part2_solution(input: &str) -> Result<Answer, AocError> {
+    let parsed_data = timing::parse(|| parse_input(input))?;
+    Ok(day8_part2(&parsed_data)?.to_string())
 }
 
 #[cfg(test)]
@@ -266,7 +206,7 @@ mod tests {
         cdfeb fcadb cdfeb cdbaf";
 
         let pattern = Pattern::new(data);
-        let map = pattern.deduce_numbers_from_unique_pattern();
+        let map = pattern.deduce_numbers_from_unique_pattern().unwrap();
 
         // println!("Data: {:?}", map);
 
@@ -281,7 +221,7 @@ mod tests {
         assert_eq!(map.get(&sort("cagedb")).unwrap(), &0);
         assert_eq!(map.get(&sort("ab")).unwrap(), &1);
 
-        assert_eq!(pattern.deduce_output_value(), 5353);
+        assert_eq!(pattern.deduce_output_value().unwrap(), 5353);
 
         let data = [
             "be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb |
@@ -311,6 +251,27 @@ mod tests {
             .map(|x| Pattern::new(x))
             .collect::<Vec<Pattern>>();
 
-        assert_eq!(day8_part2(&parsed_data), 61229);
+        assert_eq!(day8_part2(&parsed_data).unwrap(), 61229);
+    }
+
+    #[test]
+    fn test_day8_unsolvable() {
+        // NOTE, Two patterns of one segment, no digit lights a single one
+        let pattern =
+            Pattern::new("a b cf acf bcdf acdeg acdfg abdfg abdefg abcdefg | cf cf cf cf");
+        let error = pattern.deduce_output_value().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "no answer, no wiring shows every digit for `a b cf acf bcdf acdeg acdfg abdfg abdefg abcdefg`"
+        );
+
+        let pattern = Pattern::new(
+            "abcefg cf acdeg acdfg bcdf abdfg abdefg acf abcdefg abcdfg | cf bd cf cf",
+        );
+        let error = pattern.deduce_output_value().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "no answer, output `bd` matches none of the patterns"
+        );
     }
 }
@@ -1,82 +1,47 @@
-use std::{collections::HashSet, path::PathBuf};
-
-fn is_lowest(data: &Vec<Vec<u8>>, point: (usize, usize)) -> bool {
-    let x_max_length = data.len();
-    let y_max_length = data[0].len();
-    let (x_point, y_point) = point;
-    let current_data = data[x_point][y_point];
-
-    let mut is_lowest = true;
-    if x_point.checked_sub(1) != None {
-        is_lowest = is_lowest && data[x_point - 1][y_point] > current_data;
-    }
-
-    // NOTE, Can create a overflow_add(x_point, 1, max_length) -> Option<usize>
-    // For now this is fine
-    if x_point.checked_add(1) < Some(x_max_length) {
-        is_lowest = is_lowest && data[x_point + 1][y_point] > current_data;
-    }
-
-    if y_point.checked_sub(1) != None {
-        is_lowest = is_lowest && data[x_point][y_point - 1] > current_data;
-    }
-
-    if y_point.checked_add(1) < Some(y_max_length) {
-        is_lowest = is_lowest && data[x_point][y_point + 1] > current_data;
-    }
-    is_lowest
+use std::collections::HashSet;
+
+use common::{
+    aoc,
+    image::Rgb,
+    timing, visualize,
+    visualize::{Cell, Color, Frame, Render},
+    Answer, AocError, Grid, ParseError, Point, Simulation,
+};
+
+fn is_lowest(data: &Grid<u8>, point: Point) -> bool {
+    data.neighbours4(point).all(|n| data[n] > data[point])
 }
 
-fn get_lowest_points(data: &Vec<Vec<u8>>) -> Vec<(usize, usize)> {
-    let mut lowest_points = Vec::new();
-    data.iter().enumerate().for_each(|(x_counter, x_data)| {
-        x_data.iter().enumerate().for_each(|(y_counter, _)| {
-            // Current: x_counter, y_counter
-            // Check (x_counter -1, y_counter), (x_counter + 1, y_counter)
-            // Check (x_counter, y_counter - 1), (x_counter, y_counter + 1)
-            let is_lowest = is_lowest(data, (x_counter, y_counter));
-            if is_lowest {
-                lowest_points.push((x_counter, y_counter));
-            }
-        })
-    });
-    lowest_points
+fn get_lowest_points(data: &Grid<u8>) -> Vec<Point> {
+    data.points().filter(|&p| is_lowest(data, p)).collect()
 }
 
-fn day9_part1(data: &Vec<Vec<u8>>) -> u32 {
+fn day9_part1(data: &Grid<u8>) -> u32 {
     get_lowest_points(data)
         .iter()
-        .map(|p| data[p.0][p.1] as u32 + 1)
+        .map(|&p| data[p] as u32 + 1)
         .sum()
 }
 
-fn parse_values_from_file(path: &PathBuf) -> Vec<Vec<u8>> {
-    let data = common::read_file(path);
-
-    data.trim()
-        .split("\n")
-        .map(|x| {
-            x.trim()
-                .as_bytes()
-                .iter()
-                .map(|y| y - 0x30)
-                .collect::<Vec<u8>>()
-        })
-        .collect::<Vec<Vec<u8>>>()
+fn parse_input(input: &str) -> Result<Grid<u8>, ParseError> {
+    Grid::parse(input, "a height between 0 and 9", |c| {
+        c.to_digit(10).map(|d| d as u8)
+    })
 }
 
-pub fn day9_part1_solution(path: &PathBuf) -> String {
-    let parsed_data = parse_values_from_file(path);
-    day9_part1(&parsed_data).to_string()
+#[aoc(year = 2021, day = 9, part = 1)]
+pub fn day9_part1_solution(input: &str) -> Result<Answer, AocError> {
+    let parsed_data = timing::parse(|| parse_input(input))?;
+    Ok(day9_part1(&parsed_data).to_string())
 }
 
 struct Basin<'a> {
-    data: &'a Vec<Vec<u8>>,
-    smallest_point: (usize, usize),
+    data: &'a Grid<u8>,
+    smallest_point: Point,
 }
 
 impl<'a> Basin<'a> {
-    fn new(data: &'a Vec<Vec<u8>>, smallest_point: (usize, usize)) -> Self {
+    fn new(data: &'a Grid<u8>, smallest_point: Point) -> Self {
         Basin {
             data,
             smallest_point,
@@ -84,24 +49,23 @@ impl<'a> Basin<'a> {
     }
 
     // Get all points in the basin (from the smallest point)
-    fn get_all_points(&self) -> HashSet<(usize, usize)> {
+    fn get_all_points(&self) -> HashSet<Point> {
         let mut verify_points = HashSet::new();
         verify_points.insert(self.smallest_point);
 
         let mut checked_points = Vec::new();
         checked_points.push(self.smallest_point);
 
-        while !checked_points.is_empty() {
-            let point = checked_points.pop().unwrap();
-            let mut nearest_points: Vec<(usize, usize)> = self
+        while let Some(point) = checked_points.pop() {
+            let mut nearest_points: Vec<Point> = self
                 .get_nearest_points(point)
                 .iter()
                 .filter(|p| !verify_points.contains(p))
-                .map(|&p| p)
+                .copied()
                 .collect();
 
             for np in &nearest_points {
-                verify_points.insert(np.clone());
+                verify_points.insert(*np);
             }
             checked_points.append(&mut nearest_points);
         }
@@ -109,53 +73,17 @@ impl<'a> Basin<'a> {
         verify_points
     }
 
-    /// Get the nearest point (top, down, left, right) from the given point
+    /// Get the nearest point (up, right, down, left) from the given point
     /// As long as the nearest point is a valid point and not part of the basin (9)
-    fn get_nearest_points(&self, from_point: (usize, usize)) -> Vec<(usize, usize)> {
-        let x_len = self.data.len() - 1;
-        let y_len = self.data[0].len() - 1;
-
-        let x = from_point.0;
-        let y = from_point.1;
-
-        // up, down, left, right
-        let mut points = Vec::new();
-
-        // left
-        if x.checked_sub(1) != None {
-            // self.data[x - 1][y];
-            points.push((x - 1, y));
-        }
-
-        // right
-        if x.checked_add(1) <= Some(x_len) {
-            // self.data[x + 1][y];
-            points.push((x + 1, y));
-        }
-
-        // down
-        if y.checked_sub(1) != None {
-            // self.data[x][y - 1];
-            points.push((x, y - 1));
-        }
-
-        // up
-        if y.checked_add(1) <= Some(y_len) {
-            // self.data[x][y + 1];
-            points.push((x, y + 1));
-        }
-        // println!("Points: {:?}", points);
-        let points: Vec<(usize, usize)> = points
-            .iter()
-            .filter(|&&p| self.data[p.0][p.1] != 9)
-            .map(|p| *p)
-            .collect();
-
-        points
+    fn get_nearest_points(&self, from_point: Point) -> Vec<Point> {
+        self.data
+            .neighbours4(from_point)
+            .filter(|&p| self.data[p] != 9)
+            .collect()
     }
 }
 
-fn day9_part2(data: &Vec<Vec<u8>>) -> usize {
+fn day9_part2(data: &Grid<u8>) -> Result<usize, AocError> {
     let mut set = Vec::new();
 
     let lowest_points = get_lowest_points(data);
@@ -169,14 +97,94 @@ fn day9_part2(data: &Vec<Vec<u8>>) -> usize {
     set.iter()
         .rev()
         .take(3)
-        .map(|x| *x)
-        .reduce(|accum, item| (accum * item))
-        .unwrap()
+        .copied()
+        .reduce(|accum, item| accum * item)
+        .ok_or_else(|| AocError::Unsolvable("the heightmap has no low point".to_string()))
+}
+
+#[aoc(year = 2021, day = 9, part = 2)]
+pub fn day9_part2_solution(input: &str) -> Result<Answer, AocError> {
+    let parsed_data = timing::parse(|| parse_input(input))?;
+    Ok(day9_part2(&parsed_data)?.to_string())
+}
+
+/// Reveals the basins one at a time, coloured from blue for the smallest to
+/// red for the largest
+struct BasinFill {
+    heights: Grid<u8>,
+    basins: Vec<HashSet<Point>>,
+    filled: usize,
 }
 
-pub fn day9_part2_solution(path: &PathBuf) -> String {
-    let parsed_data = parse_values_from_file(path);
-    day9_part2(&parsed_data).to_string()
+impl BasinFill {
+    fn new(heights: Grid<u8>) -> Self {
+        let basins = get_lowest_points(&heights)
+            .into_iter()
+            .map(|p| Basin::new(&heights, p).get_all_points())
+            .collect();
+        BasinFill {
+            heights,
+            basins,
+            filled: 0,
+        }
+    }
+}
+
+impl Simulation for BasinFill {
+    type State = usize;
+
+    fn step(&mut self) -> bool {
+        if self.filled == self.basins.len() {
+            return false;
+        }
+        self.filled += 1;
+        true
+    }
+
+    fn state(&self) -> Self::State {
+        self.filled
+    }
+}
+
+impl Render for BasinFill {
+    fn render(&self) -> Frame {
+        let mut frame = self.heights.map(|&h| {
+            if h == 9 {
+                Cell::new('#')
+            } else {
+                Cell::new('.')
+            }
+        });
+
+        let mut sizes = self.basins.iter().map(|b| b.len()).collect::<Vec<_>>();
+        sizes.sort_unstable();
+        let (Some(&smallest), Some(&largest)) = (sizes.first(), sizes.last()) else {
+            return frame;
+        };
+        let third_largest = sizes[sizes.len().saturating_sub(3)];
+        for basin in &self.basins[..self.filled] {
+            let t = (basin.len() - smallest) as f64 / (largest - smallest).max(1) as f64;
+            let Rgb(r, g, b) = Rgb(40, 90, 230).lerp(Rgb(230, 50, 40), t);
+            for &point in basin {
+                let symbol = char::from(b'0' + self.heights[point]);
+                let mut cell = Cell::colored(symbol, Color::Rgb(r, g, b));
+                if basin.len() >= third_largest {
+                    cell = cell.bold();
+                }
+                frame[point] = cell;
+            }
+        }
+        frame
+    }
+
+    fn caption(&self) -> String {
+        format!("{}/{} basins", self.filled, self.basins.len())
+    }
+}
+
+#[visualize(year = 2021, day = 9)]
+fn basin_fill(input: &str) -> Result<BasinFill, AocError> {
+    Ok(BasinFill::new(parse_input(input)?))
 }
 
 #[cfg(test)]
@@ -197,7 +205,7 @@ mod tests {
             .iter()
             .map(|x| x.as_bytes().iter().map(|y| y - 0x30).collect::<Vec<u8>>())
             .collect::<Vec<Vec<u8>>>();
-        // println!("Parsed Data: {:?}", parsed_data);
+        let parsed_data = Grid::from_rows(parsed_data);
 
         assert_eq!(day9_part1(&parsed_data), 15);
     }
@@ -212,32 +220,61 @@ mod tests {
             "9899965678",
         ];
 
-        let parsed_data = map
-            .iter()
-            .map(|x| x.as_bytes().iter().map(|y| y - 0x30).collect::<Vec<u8>>())
-            .collect::<Vec<Vec<u8>>>();
-        // println!("Parsed Data: {:?}", parsed_data);
-        assert_eq!(parsed_data[0][1], 1);
-        assert_eq!(parsed_data[0][9], 0);
+        let parsed_data = parse_input(&map.join("\n")).unwrap();
+        assert_eq!(parsed_data[Point::new(1, 0)], 1);
+        assert_eq!(parsed_data[Point::new(9, 0)], 0);
 
-        let basin = Basin::new(&parsed_data, (0, 9));
-        assert_eq!(basin.get_nearest_points((0, 1)), vec![(0, 0)]);
-        assert_eq!(basin.get_nearest_points((0, 9)), vec![(1, 9), (0, 8)]);
+        let basin = Basin::new(&parsed_data, Point::new(9, 0));
+        assert_eq!(
+            basin.get_nearest_points(Point::new(1, 0)),
+            vec![Point::new(0, 0)]
+        );
+        assert_eq!(
+            basin.get_nearest_points(Point::new(9, 0)),
+            vec![Point::new(9, 1), Point::new(8, 0)]
+        );
         assert_eq!(
             basin.get_all_points(),
-            HashSet::from([
-                (0, 9),
-                (0, 8),
-                (0, 7),
-                (0, 6),
-                (0, 5),
-                (1, 6),
-                (1, 8),
-                (1, 9),
-                (2, 9),
-            ])
+            HashSet::from(
+                [
+                    (9, 0),
+                    (8, 0),
+                    (7, 0),
+                    (6, 0),
+                    (5, 0),
+                    (6, 1),
+                    (8, 1),
+                    (9, 1),
+                    (9, 2),
+                ]
+                .map(Point::from)
+            )
         );
 
-        assert_eq!(day9_part2(&parsed_data), 1134);
+        assert_eq!(day9_part2(&parsed_data).unwrap(), 1134);
+
+        let error = day9_part2_solution("55\n55").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "no answer, the heightmap has no low point"
+        );
+    }
+
+    #[test]
+    fn test_basin_fill() {
+        let map = "2199943210\n3987894921\n9856789892\n8767896789\n9899965678";
+        let mut fill = BasinFill::new(parse_input(map).unwrap());
+        assert_eq!(fill.render().row(0)[..2], [Cell::new('.'), Cell::new('.')]);
+        assert_eq!(fill.run(10), 4);
+        assert_eq!(fill.caption(), "4/4 basins");
+
+        let frame = fill.render();
+        assert_eq!(frame.to_string().lines().next(), Some("21###43210"));
+        // NOTE, The basins of size 3 and 14 are the smallest and the largest
+        let top_left = frame[Point::new(0, 0)];
+        assert_eq!(top_left.color, Some(Color::Rgb(40, 90, 230)));
+        assert!(!top_left.bold);
+        assert!(frame[Point::new(9, 0)].bold);
+        assert_eq!(frame[Point::new(2, 2)].color, Some(Color::Rgb(230, 50, 40)));
     }
 }
@@ -1,6 +1,6 @@
-use std::path::PathBuf;
+use common::{aoc, timing, Answer, AocError, ParseError, Span};
 
-fn calories_carried_by_each_elf(data: &Vec<Option<u32>>) -> Vec<u32> {
+fn calories_carried_by_each_elf(data: &[Option<u32>]) -> Vec<u32> {
     let mut elves_calories = Vec::new();
     let mut current_calorie_counter = 0;
     data.iter().for_each(|d| match d {
@@ -18,40 +18,38 @@ fn calories_carried_by_each_elf(data: &Vec<Option<u32>>) -> Vec<u32> {
     elves_calories
 }
 
-fn day1_part1(data: &Vec<Option<u32>>) -> u32 {
+fn day1_part1(data: &[Option<u32>]) -> u32 {
     *calories_carried_by_each_elf(data).iter().max().unwrap()
 }
 
-fn parse_values_from_file(path: &PathBuf) -> Vec<Option<u32>> {
-    let input = common::read_file(path);
-    input
+/// Blank lines, which separate the elves, are parsed as `None`
+fn parse_input(input: &str) -> Result<Vec<Option<u32>>, ParseError> {
+    Span::new(input)
         .trim()
-        .split("\n")
-        .map(|l| {
-            let parsed = l.trim().parse::<u32>();
-            if parsed.is_ok() {
-                Some(parsed.unwrap())
-            } else {
-                None
-            }
+        .lines()
+        .map(|l| match l.trim() {
+            l if l.is_empty() => Ok(None),
+            l => Ok(Some(l.parse::<u32>()?)),
         })
-        .collect::<Vec<Option<u32>>>()
+        .collect()
 }
 
-pub fn day1_part1_solution(path: &PathBuf) -> String {
-    let parsed_data = parse_values_from_file(path);
-    day1_part1(&parsed_data).to_string()
+#[aoc(year = 2022, day = 1, part = 1)]
+pub fn day1_part1_solution(input: &str) -> Result<Answer, AocError> {
+    let parsed_data = timing::parse(|| parse_input(input))?;
+    Ok(day1_part1(&parsed_data).to_string())
 }
 
-fn day1_part2(data: &Vec<Option<u32>>) -> u32 {
+fn day1_part2(data: &[Option<u32>]) -> u32 {
     let mut calories_carried = calories_carried_by_each_elf(data);
     calories_carried.sort_by(|a, b| b.cmp(a));
     calories_carried.iter().take(3).sum()
 }
 
-pub fn day1_part2_solution(path: &PathBuf) -> String {
-    let parsed_data = parse_values_from_file(path);
-    day1_part2(&parsed_data).to_string()
+#[aoc(year = 2022, day = 1, part = 2)]
+pub fn day1_part2_solution(input: &str) -> Result<Answer, AocError> {
+    let parsed_data = timing::parse(|| parse_input(input))?;
+    Ok(day1_part2(&parsed_data).to_string())
 }
 
 #[cfg(test)]
@@ -77,14 +75,7 @@ mod tests {
         let split_data = input
             .trim()
             .split("\n")
-            .map(|l| {
-                let parsed = l.trim().parse::<u32>();
-                if parsed.is_ok() {
-                    Some(parsed.unwrap())
-                } else {
-                    None
-                }
-            })
+            .map(|l| l.trim().parse::<u32>().ok())
             .collect::<Vec<Option<u32>>>();
         split_data
     }
@@ -116,7 +107,7 @@ mod tests {
 
     #[test]
     fn test_day1_part1_solution() {
-        let solution = day1_part1_solution(&PathBuf::new().join("inputs").join("day1_input.txt"));
+        let solution = day1_part1_solution(&common::read_input(2022, 1).unwrap()).unwrap();
         println!("Solution : {:?}", solution);
     }
 
@@ -1,6 +1,7 @@
-use std::path::PathBuf;
+use common::{aoc, timing, Answer, AocError, ParseError, Span};
 
 #[derive(Debug, PartialEq, Copy, Clone)]
+#[allow(clippy::upper_case_acronyms)]
 enum RPS {
     Rock,
     Paper,
@@ -18,6 +19,7 @@ impl RPS {
         opponent == player
     }
 
+    #[allow(dead_code)]
     fn is_lose(opponent: &RPS, player: &RPS) -> bool {
         !RPS::is_win(opponent, player) && !RPS::is_draw(opponent, player)
     }
@@ -31,7 +33,7 @@ impl RPS {
     }
 }
 
-fn day2_part1(data: &Vec<(RPS, RPS)>) -> u32 {
+fn day2_part1(data: &[(RPS, RPS)]) -> u32 {
     data.iter()
         .map(|(prediction, player_move)| {
             let player_move_score = RPS::to_num(player_move);
@@ -40,41 +42,47 @@ fn day2_part1(data: &Vec<(RPS, RPS)>) -> u32 {
             } else if RPS::is_draw(prediction, player_move) {
                 3 + player_move_score
             } else {
-                0 + player_move_score
+                player_move_score
             }
         })
         .sum::<u32>()
 }
 
-fn parse_values_from_file(path: &PathBuf) -> Vec<(RPS, RPS)> {
-    let input = common::read_file(path);
-    input
+fn parse_prediction(c: Span) -> Result<RPS, ParseError> {
+    match c.as_str() {
+        "A" => Ok(RPS::Rock),
+        "B" => Ok(RPS::Paper),
+        "C" => Ok(RPS::Scissor),
+        _ => Err(c.error("`A`, `B` or `C`")),
+    }
+}
+
+fn split_round(line: Span) -> Result<(Span, Span), ParseError> {
+    line.trim().split_once(" ")
+}
+
+fn parse_input(input: &str) -> Result<Vec<(RPS, RPS)>, ParseError> {
+    Span::new(input)
         .trim()
-        .split("\n")
+        .lines()
         .map(|line| {
-            let rps_pair = line.trim().split(" ").collect::<Vec<&str>>();
-            let prediction = match rps_pair[0] {
-                "A" => RPS::Rock,
-                "B" => RPS::Paper,
-                "C" => RPS::Scissor,
-                _ => panic!("Invalid option"),
-            };
-
-            let player_move = match rps_pair[1] {
+            let (prediction, player_move) = split_round(line)?;
+            let player_move = match player_move.as_str() {
                 "X" => RPS::Rock,
                 "Y" => RPS::Paper,
                 "Z" => RPS::Scissor,
-                _ => panic!("Invalid option"),
+                _ => return Err(player_move.error("`X`, `Y` or `Z`")),
             };
 
-            (prediction, player_move)
+            Ok((parse_prediction(prediction)?, player_move))
         })
-        .collect::<Vec<(RPS, RPS)>>()
+        .collect()
 }
 
-pub fn day2_part1_solution(path: &PathBuf) -> String {
-    let parsed_data = parse_values_from_file(path);
-    day2_part1(&parsed_data).to_string()
+#[aoc(year = 2022, day = 2, part = 1)]
+pub fn day2_part1_solution(input: &str) -> Result<Answer, AocError> {
+    let parsed_data = timing::parse(|| parse_input(input))?;
+    Ok(day2_part1(&parsed_data).to_string())
 }
 
 enum Action {
@@ -105,45 +113,38 @@ impl Action {
     }
 }
 
-fn day2_part2(data: &Vec<(RPS, Action)>) -> u32 {
+fn day2_part2(data: &[(RPS, Action)]) -> u32 {
     data.iter()
         .map(|(opponent, player_action)| match player_action {
-            Action::Lose => 0 + RPS::to_num(&Action::for_lose(opponent)),
+            Action::Lose => RPS::to_num(&Action::for_lose(opponent)),
             Action::Draw => 3 + RPS::to_num(&Action::for_draw(opponent)),
             Action::Win => 6 + RPS::to_num(&Action::for_win(opponent)),
         })
         .sum()
 }
 
-fn parse_values_from_file_for_part2(path: &PathBuf) -> Vec<(RPS, Action)> {
-    let input = common::read_file(path);
-    input
+fn parse_input_for_part2(input: &str) -> Result<Vec<(RPS, Action)>, ParseError> {
+    Span::new(input)
         .trim()
-        .split("\n")
+        .lines()
         .map(|line| {
-            let rps_pair = line.trim().split(" ").collect::<Vec<&str>>();
-            let prediction = match rps_pair[0] {
-                "A" => RPS::Rock,
-                "B" => RPS::Paper,
-                "C" => RPS::Scissor,
-                _ => panic!("Invalid option"),
-            };
-
-            let player_move = match rps_pair[1] {
+            let (prediction, player_move) = split_round(line)?;
+            let player_move = match player_move.as_str() {
                 "X" => Action::Lose,
                 "Y" => Action::Draw,
                 "Z" => Action::Win,
-                _ => panic!("Invalid option"),
+                _ => return Err(player_move.error("`X`, `Y` or `Z`")),
             };
 
-            (prediction, player_move)
+            Ok((parse_prediction(prediction)?, player_move))
         })
-        .collect::<Vec<(RPS, Action)>>()
+        .collect()
 }
 
-pub fn day2_part2_solution(path: &PathBuf) -> String {
-    let parsed_data = parse_values_from_file_for_part2(path);
-    day2_part2(&parsed_data).to_string()
+#[aoc(year = 2022, day = 2, part = 2)]
+pub fn day2_part2_solution(input: &str) -> Result<Answer, AocError> {
+    let parsed_data = timing::parse(|| parse_input_for_part2(input))?;
+    Ok(day2_part2(&parsed_data).to_string())
 }
 
 #[cfg(test)]
@@ -225,7 +226,7 @@ mod tests {
                 } else if RPS::is_draw(prediction, player_move) {
                     3 + RPS::to_num(player_move)
                 } else {
-                    0 + RPS::to_num(player_move)
+                    RPS::to_num(player_move)
                 }
             })
             .sum::<u32>();
@@ -1,4 +1,6 @@
-use std::{collections::HashSet, path::PathBuf};
+use std::collections::HashSet;
+
+use common::{aoc, timing, Answer, AocError};
 
 fn create_hashset(from: &str) -> HashSet<char> {
     let mut hset = HashSet::new();
@@ -9,16 +11,16 @@ fn create_hashset(from: &str) -> HashSet<char> {
 }
 
 fn to_priority(character: char) -> u32 {
-    if character >= 'a' && character <= 'z' {
+    if character.is_ascii_lowercase() {
         (character as u32 - 'a' as u32) + 1
-    } else if character >= 'A' && character <= 'Z' {
+    } else if character.is_ascii_uppercase() {
         (character as u32 - 'A' as u32) + 27
     } else {
         0
     }
 }
 
-fn day3_part1(data: &Vec<String>) -> u32 {
+fn day3_part1(data: &[String]) -> u32 {
     data.iter()
         .map(|line| {
             let len = line.len() / 2;
@@ -29,7 +31,7 @@ fn day3_part1(data: &Vec<String>) -> u32 {
             first_hset
                 .iter()
                 .map(|c| {
-                    if second_hset.contains(&c) {
+                    if second_hset.contains(c) {
                         to_priority(*c)
                     } else {
                         0
@@ -40,8 +42,7 @@ fn day3_part1(data: &Vec<String>) -> u32 {
         .sum::<u32>()
 }
 
-fn parse_values_from_file(path: &PathBuf) -> Vec<String> {
-    let input = common::read_file(path);
+fn parse_input(input: &str) -> Vec<String> {
     input
         .trim()
         .split("\n")
@@ -49,9 +50,10 @@ fn parse_values_from_file(path: &PathBuf) -> Vec<String> {
         .collect()
 }
 
-pub fn day3_part1_solution(path: &PathBuf) -> String {
-    let parsed_data = parse_values_from_file(path);
-    day3_part1(&parsed_data).to_string()
+#[aoc(year = 2022, day = 3, part = 1)]
+pub fn day3_part1_solution(input: &str) -> Result<Answer, AocError> {
+    let parsed_data = timing::parse(|| parse_input(input));
+    Ok(day3_part1(&parsed_data).to_string())
 }
 
 fn find_badge(elves_bags: &[String]) -> u32 {
@@ -61,25 +63,25 @@ fn find_badge(elves_bags: &[String]) -> u32 {
 
     first
         .intersection(&second)
-        .map(|c| *c)
+        .copied()
         .collect::<HashSet<char>>()
         .intersection(&third)
         .map(|c| to_priority(*c))
         .sum()
 }
 
-fn day3_part2(data: &Vec<String>) -> u32 {
-    data.chunks(3).map(|data| find_badge(data)).sum()
+fn day3_part2(data: &[String]) -> u32 {
+    data.chunks(3).map(find_badge).sum()
 }
 
-pub fn day3_part2_solution(path: &PathBuf) -> String {
-    let parsed_data = parse_values_from_file(path);
-    day3_part2(&parsed_data).to_string()
+#[aoc(year = 2022, day = 3, part = 2)]
+pub fn day3_part2_solution(input: &str) -> Result<Answer, AocError> {
+    let parsed_data = timing::parse(|| parse_input(input));
+    Ok(day3_part2(&parsed_data).to_string())
 }
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashSet;
 
     use super::*;
 
@@ -113,7 +115,7 @@ mod tests {
                 first_hset
                     .iter()
                     .map(|c| {
-                        if second_hset.contains(&c) {
+                        if second_hset.contains(c) {
                             to_priority(*c)
                         } else {
                             0
@@ -1,80 +1,63 @@
-use std::path::PathBuf;
+use common::{aoc, interval::Interval, timing, Answer, AocError, ParseError, Span};
 
-#[derive(Debug)]
-struct Range {
-    start: u32,
-    end: u32,
-}
-
-impl Range {
-    fn new(start: u32, end: u32) -> Self {
-        Self { start, end }
-    }
-
-    fn new_from_str(line: &str) -> Self {
-        let split_data = line.trim().split("-").collect::<Vec<&str>>();
-        Self {
-            start: u32::from_str_radix(split_data[0], 10).unwrap(),
-            end: u32::from_str_radix(split_data[1], 10).unwrap(),
-        }
-    }
-
-    /// This function only works for positive numbers
-    /// [other.start, other.end] is present between [start, end]
-    fn fully_contains(&self, other: &Range) -> bool {
-        self.start <= other.start && self.end >= other.end
-    }
+/// The section ids assigned to two elves
+type Pair = (Interval<u32>, Interval<u32>);
 
-    /// other.start is present between [start, end] OR
-    /// other.end is present between [start, end]
-    fn overlaps(&self, other: &Range) -> bool {
-        (self.start <= other.start && self.end >= other.start)
-            || (self.start <= other.end && self.end >= other.end)
+/// A `start-end` range of section ids, both included
+fn parse_range(line: Span) -> Result<Interval<u32>, ParseError> {
+    let (start, end) = line.trim().split_once("-")?;
+    let (first, last) = (start.parse::<u32>()?, end.parse::<u32>()?);
+    if first > last {
+        return Err(line
+            .trim()
+            .error("a range that doesn't end before it starts"));
     }
+    Interval::inclusive(first, last)
+        .ok_or_else(|| end.error(format!("a section id below {}", u32::MAX)))
 }
 
-fn day4_part1(data: &Vec<(Range, Range)>) -> usize {
+fn day4_part1(data: &[Pair]) -> usize {
     data.iter()
-        .filter(|(first, second)| first.fully_contains(second) || second.fully_contains(first))
+        .filter(|(first, second)| {
+            first.contains_interval(second) || second.contains_interval(first)
+        })
         .count()
 }
 
-fn parse_values_from_file(path: &PathBuf) -> Vec<(Range, Range)> {
-    let input = common::read_file(path);
-    input
+fn parse_input(input: &str) -> Result<Vec<Pair>, ParseError> {
+    Span::new(input)
         .trim()
-        .split("\n")
+        .lines()
         .map(|line| {
-            let split_line = line.trim().split(",").collect::<Vec<&str>>();
-            (
-                Range::new_from_str(split_line[0]),
-                Range::new_from_str(split_line[1]),
-            )
+            let (first, second) = line.trim().split_once(",")?;
+            Ok((parse_range(first)?, parse_range(second)?))
         })
-        .collect::<Vec<(Range, Range)>>()
+        .collect()
 }
 
-pub fn day4_part1_solution(path: &PathBuf) -> String {
-    let parsed_data = parse_values_from_file(path);
-    day4_part1(&parsed_data).to_string()
+#[aoc(year = 2022, day = 4, part = 1)]
+pub fn day4_part1_solution(input: &str) -> Result<Answer, AocError> {
+    let parsed_data = timing::parse(|| parse_input(input))?;
+    Ok(day4_part1(&parsed_data).to_string())
 }
 
-fn day4_part2(data: &Vec<(Range, Range)>) -> usize {
+fn day4_part2(data: &[Pair]) -> usize {
     data.iter()
-        .filter(|(first, second)| first.overlaps(second) || second.overlaps(first))
+        .filter(|(first, second)| first.overlaps(second))
         .count()
 }
 
-pub fn day4_part2_solution(path: &PathBuf) -> String {
-    let parsed_data = parse_values_from_file(path);
-    day4_part2(&parsed_data).to_string()
+#[aoc(year = 2022, day = 4, part = 2)]
+pub fn day4_part2_solution(input: &str) -> Result<Answer, AocError> {
+    let parsed_data = timing::parse(|| parse_input(input))?;
+    Ok(day4_part2(&parsed_data).to_string())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    fn test_parse_input() -> Vec<(Range, Range)> {
+    fn test_parse_input() -> Vec<Pair> {
         let input = "2-4,6-8
         2-3,4-5
         5-7,7-9
@@ -82,33 +65,22 @@ mod tests {
         6-6,4-6
         2-6,4-8";
 
-        let parsed_data = input
-            .trim()
-            .split("\n")
-            .map(|line| {
-                let split_line = line.trim().split(",").collect::<Vec<&str>>();
-                (
-                    Range::new_from_str(split_line[0]),
-                    Range::new_from_str(split_line[1]),
-                )
-            })
-            .collect::<Vec<(Range, Range)>>();
-        parsed_data
+        parse_input(input).unwrap()
     }
 
     #[test]
     fn test_day4_part1() {
         {
-            let first = Range::new(6, 6);
-            let second = Range::new(4, 6);
-            assert!(second.fully_contains(&first));
+            let first = Interval::inclusive(6, 6).unwrap();
+            let second = Interval::inclusive(4, 6).unwrap();
+            assert!(second.contains_interval(&first));
         }
 
         {
-            let first = Range::new(2, 8);
-            let second = Range::new(3, 7);
-            assert!(!second.fully_contains(&first));
-            assert!(first.fully_contains(&second));
+            let first = Interval::inclusive(2, 8).unwrap();
+            let second = Interval::inclusive(3, 7).unwrap();
+            assert!(!second.contains_interval(&first));
+            assert!(first.contains_interval(&second));
         }
 
         let parsed_data = test_parse_input();
@@ -120,4 +92,15 @@ mod tests {
         let parsed_data = test_parse_input();
         assert_eq!(day4_part2(&parsed_data), 4);
     }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = parse_input("2-4,6-8\n5-3,1-9").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 1: expected a range that doesn't end before it starts, found \"5-3\""
+        );
+        let error = parse_input("1-4294967295,1-2").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 3));
+    }
 }
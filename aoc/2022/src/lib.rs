@@ -0,0 +1,9 @@
+mod day1;
+mod day2;
+mod day3;
+mod day4;
+
+#[cfg(test)]
+mod examples {
+    include!(concat!(env!("OUT_DIR"), "/examples.rs"));
+}
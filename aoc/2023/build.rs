@@ -0,0 +1,3 @@
+fn main() {
+    common::examples::generate_tests(2023);
+}
@@ -1,6 +1,8 @@
 use std::collections::HashMap;
 
-fn parse_input(input: String) -> Vec<String> {
+use common::{aoc, timing, Answer, AocError};
+
+fn parse_input(input: &str) -> Vec<String> {
     input
         .trim()
         .split('\n')
@@ -8,8 +10,9 @@ fn parse_input(input: String) -> Vec<String> {
         .collect::<Vec<String>>()
 }
 
-pub fn day1_part1_solution(input: String) -> String {
-    let parsed = parse_input(input);
+#[aoc(year = 2023, day = 1, part = 1)]
+pub fn day1_part1_solution(input: &str) -> Result<Answer, AocError> {
+    let parsed = timing::parse(|| parse_input(input));
     let ans = parsed
         .iter()
         .map(|l| {
@@ -22,10 +25,10 @@ pub fn day1_part1_solution(input: String) -> String {
             number
         })
         .sum::<u32>();
-    ans.to_string()
+    Ok(ans.to_string())
 }
 
-fn find_all(line: &String, map: &HashMap<&str, u32>) -> Vec<u32> {
+fn find_all(line: &str, map: &HashMap<&str, u32>) -> Vec<u32> {
     let mut start = 0;
     let mut end = start + 1;
 
@@ -41,9 +44,8 @@ fn find_all(line: &String, map: &HashMap<&str, u32>) -> Vec<u32> {
 
         // Check if the slice is a number
         if current_slice.len() == 1 {
-            let parsed = current_slice.parse::<u32>();
-            if parsed.is_ok() {
-                stored_numbers.push(parsed.unwrap());
+            if let Ok(parsed) = current_slice.parse::<u32>() {
+                stored_numbers.push(parsed);
                 start += 1;
                 end = start + 1;
                 continue;
@@ -75,7 +77,8 @@ fn find_all(line: &String, map: &HashMap<&str, u32>) -> Vec<u32> {
     stored_numbers
 }
 
-pub fn day1_part2_solution(input: String) -> String {
+#[aoc(year = 2023, day = 1, part = 2)]
+pub fn day1_part2_solution(input: &str) -> Result<Answer, AocError> {
     let map = HashMap::from([
         ("one", 1),
         ("two", 2),
@@ -87,7 +90,7 @@ pub fn day1_part2_solution(input: String) -> String {
         ("eight", 8),
         ("nine", 9),
     ]);
-    let parsed = parse_input(input);
+    let parsed = timing::parse(|| parse_input(input));
     let ans = parsed
         .iter()
         .map(|l| {
@@ -96,7 +99,7 @@ pub fn day1_part2_solution(input: String) -> String {
         })
         // .inspect(|d| println!("{d}"))
         .sum::<u32>();
-    ans.to_string()
+    Ok(ans.to_string())
 }
 
 #[cfg(test)]
@@ -109,15 +112,15 @@ mod tests {
 
     #[test]
     fn test_day1_part1() {
-        let parsed = parse_input(INPUT_STR.into());
+        let parsed = parse_input(INPUT_STR);
         println!("parsed: {:?}", parsed);
-        let ans = day1_part1_solution(INPUT_STR.into());
+        let ans = day1_part1_solution(INPUT_STR).unwrap();
         assert_eq!(ans, "142");
     }
 
     #[test]
     fn test_day1_part2() {
-        let parsed = parse_input(INPUT_STR2.into());
+        let parsed = parse_input(INPUT_STR2);
         println!("parsed: {:?}", parsed);
         let map = HashMap::from([
             ("one", 1),
@@ -145,7 +148,7 @@ mod tests {
         let found_numbers = find_all(&line, &map);
         assert_eq!(found_numbers, vec![4, 9, 8, 7, 2]);
 
-        let ans = day1_part2_solution(INPUT_STR2.into());
+        let ans = day1_part2_solution(INPUT_STR2).unwrap();
         assert_eq!(ans, "281");
     }
 }
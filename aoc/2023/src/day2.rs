@@ -1,30 +1,30 @@
+use common::{aoc, timing, Answer, AocError, ParseError, Span};
+
 #[derive(Debug)]
 struct Game {
     id: usize,
     sets: Vec<(usize, usize, usize)>,
 }
 
-fn parse_input(input: String) -> Vec<Game> {
-    input
+fn parse_input(input: &str) -> Result<Vec<Game>, ParseError> {
+    Span::new(input)
         .trim()
-        .split('\n')
+        .lines()
         .map(|l| {
-            let p1 = l.trim().split(':').collect::<Vec<&str>>();
-            let id = p1[0].trim().split(' ').collect::<Vec<&str>>()[1]
-                .parse::<usize>()
-                .unwrap();
+            let (id, sets) = l.trim().strip_prefix("Game ")?.split_once(":")?;
+            let id = id.parse::<usize>()?;
 
-            let sets = p1[1]
+            let sets = sets
                 .trim()
-                .split(';')
+                .split(";")
                 .map(|d| {
                     let mut r = 0;
                     let mut g = 0;
                     let mut b = 0;
-                    d.split(',').for_each(|c| {
-                        let color = c.trim().split(' ').collect::<Vec<&str>>();
-                        let num = color[0].parse::<usize>().unwrap();
-                        match color[1] {
+                    for c in d.split(",") {
+                        let (num, color) = c.trim().split_once(" ")?;
+                        let num = num.parse::<usize>()?;
+                        match color.as_str() {
                             "red" => {
                                 r += num;
                             }
@@ -34,21 +34,22 @@ fn parse_input(input: String) -> Vec<Game> {
                             "blue" => {
                                 b += num;
                             }
-                            _ => unreachable!(),
+                            _ => return Err(color.error("`red`, `green` or `blue`")),
                         }
-                    });
-                    (r, g, b)
+                    }
+                    Ok((r, g, b))
                 })
-                .collect::<Vec<(usize, usize, usize)>>();
+                .collect::<Result<Vec<(usize, usize, usize)>, ParseError>>()?;
 
             //
-            Game { id, sets }
+            Ok(Game { id, sets })
         })
-        .collect::<Vec<Game>>()
+        .collect()
 }
 
-pub fn day2_part1_solution(input: String) -> String {
-    let games = parse_input(input);
+#[aoc(year = 2023, day = 2, part = 1)]
+pub fn day2_part1_solution(input: &str) -> Result<Answer, AocError> {
+    let games = timing::parse(|| parse_input(input))?;
     let ans: usize = games
         .iter()
         .filter(|g| {
@@ -57,11 +58,12 @@ pub fn day2_part1_solution(input: String) -> String {
         })
         .map(|g| g.id)
         .sum();
-    ans.to_string()
+    Ok(ans.to_string())
 }
 
-pub fn day2_part2_solution(input: String) -> String {
-    let games = parse_input(input);
+#[aoc(year = 2023, day = 2, part = 2)]
+pub fn day2_part2_solution(input: &str) -> Result<Answer, AocError> {
+    let games = timing::parse(|| parse_input(input))?;
     let ans: usize = games
         .iter()
         .map(|g| {
@@ -71,7 +73,7 @@ pub fn day2_part2_solution(input: String) -> String {
             r_max * g_max * b_max
         })
         .sum();
-    ans.to_string()
+    Ok(ans.to_string())
 }
 
 #[cfg(test)]
@@ -82,15 +84,15 @@ mod tests {
 
     #[test]
     fn test_day2_part1() {
-        // let games = parse_input(INPUT_STR.into());
+        // let games = parse_input(INPUT_STR);
         // println!("{games:?}");
-        let ans = day2_part1_solution(INPUT_STR.into());
+        let ans = day2_part1_solution(INPUT_STR).unwrap();
         assert_eq!(ans, "8");
     }
 
     #[test]
     fn test_day2_part2() {
-        let ans = day2_part2_solution(INPUT_STR.into());
+        let ans = day2_part2_solution(INPUT_STR).unwrap();
         assert_eq!(ans, "2286");
     }
 }
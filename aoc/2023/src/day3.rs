@@ -1,3 +1,5 @@
+use common::{aoc, timing, Answer, AocError};
+
 #[derive(Debug)]
 struct Number {
     num: usize,
@@ -100,7 +102,7 @@ fn extract_symbol_from_line(line: &str) -> Vec<(usize, char)> {
 // One pass to extract numbers
 // Second pass to extract symbols
 // * Update algorithm to extract numbers + symbols in one pass
-fn parse_input(input: String) -> (Vec<Number>, Vec<Symbol>) {
+fn parse_input(input: &str) -> (Vec<Number>, Vec<Symbol>) {
     let iter = input.trim().split('\n').enumerate();
 
     let numbers = iter
@@ -127,9 +129,10 @@ fn parse_input(input: String) -> (Vec<Number>, Vec<Symbol>) {
     (numbers, symbols)
 }
 
-pub fn day3_part1_solution(input: String) -> String {
+#[aoc(year = 2023, day = 3, part = 1)]
+pub fn day3_part1_solution(input: &str) -> Result<Answer, AocError> {
     // Get numbers and symbol locations
-    let (numbers, symbols) = parse_input(input);
+    let (numbers, symbols) = timing::parse(|| parse_input(input));
     // Filter all numbers that are near any symbol locations
     let ans: usize = numbers
         .iter()
@@ -142,16 +145,17 @@ pub fn day3_part1_solution(input: String) -> String {
             }
         })
         .sum();
-    ans.to_string()
+    Ok(ans.to_string())
 }
 
-pub fn day3_part2_solution(input: String) -> String {
-    let (numbers, symbols) = parse_input(input);
+#[aoc(year = 2023, day = 3, part = 2)]
+pub fn day3_part2_solution(input: &str) -> Result<Answer, AocError> {
+    let (numbers, symbols) = timing::parse(|| parse_input(input));
     let ans: usize = symbols
         .iter()
         .map(|s| s.get_gear_ratio(&numbers).unwrap_or(0))
         .sum();
-    ans.to_string()
+    Ok(ans.to_string())
 }
 
 #[cfg(test)]
@@ -171,13 +175,13 @@ mod tests {
 
     #[test]
     fn test_day3_part1() {
-        let ans = day3_part1_solution(INPUT_STR.into());
+        let ans = day3_part1_solution(INPUT_STR).unwrap();
         assert_eq!(ans, "4361");
     }
 
     #[test]
     fn test_day3_part2() {
-        let ans = day3_part2_solution(INPUT_STR.into());
+        let ans = day3_part2_solution(INPUT_STR).unwrap();
         assert_eq!(ans, "467835");
     }
 }
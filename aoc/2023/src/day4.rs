@@ -1,5 +1,11 @@
 use std::collections::{HashMap, HashSet};
 
+use common::{
+    aoc,
+    combinators::{self, integer, lines, map, pair, prefixed, words},
+    timing, Answer, AocError, ParseError,
+};
+
 #[derive(Debug)]
 struct Card {
     id: usize,
@@ -33,46 +39,31 @@ impl Card {
     }
 }
 
-fn parse_input(input: String) -> Vec<Card> {
-    input
-        .trim()
-        .split('\n')
-        .map(|l| {
-            let card = l.split('|').collect::<Vec<&str>>();
-            let winning_numbers = card[0].split_terminator(':').collect::<Vec<&str>>();
-            let id: usize = winning_numbers[0]
-                .split_ascii_whitespace()
-                .last()
-                .unwrap()
-                .parse()
-                .unwrap();
-            let winning_numbers = winning_numbers[1]
-                .trim()
-                .split_ascii_whitespace()
-                .map(|l| l.parse::<usize>().unwrap())
-                .collect::<HashSet<usize>>();
-            let your_numbers = card[1]
-                .trim()
-                .split_ascii_whitespace()
-                .map(|l| l.parse::<usize>().unwrap())
-                .collect::<Vec<usize>>();
-            Card {
-                id,
-                winning_numbers,
-                your_numbers,
-            }
-        })
-        .collect::<Vec<Card>>()
+fn parse_input(input: &str) -> Result<Vec<Card>, ParseError> {
+    // NOTE, `Card N: <winning numbers> | <your numbers>`
+    let numbers = || words(integer::<usize>);
+    let card = prefixed(
+        "Card",
+        pair(integer::<usize>, ":", pair(numbers(), "|", numbers())),
+    );
+    let card = map(card, |(id, (winning_numbers, your_numbers))| Card {
+        id,
+        winning_numbers: winning_numbers.into_iter().collect(),
+        your_numbers,
+    });
+    combinators::parse(input, lines(card))
 }
 
-pub fn day4_part1_solution(input: String) -> String {
-    let cards = parse_input(input);
+#[aoc(year = 2023, day = 4, part = 1)]
+pub fn day4_part1_solution(input: &str) -> Result<Answer, AocError> {
+    let cards = timing::parse(|| parse_input(input))?;
     let ans: usize = cards.iter().map(|c| c.points()).sum();
-    ans.to_string()
+    Ok(ans.to_string())
 }
 
-pub fn day4_part2_solution(input: String) -> String {
-    let cards = parse_input(input);
+#[aoc(year = 2023, day = 4, part = 2)]
+pub fn day4_part2_solution(input: &str) -> Result<Answer, AocError> {
+    let cards = timing::parse(|| parse_input(input))?;
     let mut map = HashMap::new();
 
     let mut final_map: HashMap<usize, usize> = HashMap::new();
@@ -105,7 +96,7 @@ pub fn day4_part2_solution(input: String) -> String {
         }
     });
     let ans: usize = final_map.values().copied().sum();
-    ans.to_string()
+    Ok(ans.to_string())
 }
 
 #[cfg(test)]
@@ -121,16 +112,16 @@ Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
 
     #[test]
     fn test_day4_part1() {
-        let cards = parse_input(INPUT_STR.into());
+        let cards = parse_input(INPUT_STR).unwrap();
         assert_eq!(cards[0].points(), 8);
 
-        let ans = day4_part1_solution(INPUT_STR.into());
+        let ans = day4_part1_solution(INPUT_STR).unwrap();
         assert_eq!(ans, "13");
     }
 
     #[test]
     fn test_day4_part2() {
-        let ans = day4_part2_solution(INPUT_STR.into());
+        let ans = day4_part2_solution(INPUT_STR).unwrap();
         assert_eq!(ans, "30");
     }
 }
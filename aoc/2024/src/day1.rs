@@ -1,22 +1,24 @@
 use std::collections::{hash_map::Entry, HashMap};
 
-fn parse_input(input: String) -> Vec<(u32, u32)> {
-    input
+use common::{aoc, timing, Answer, AocError, ParseError, Span};
+
+fn parse_input(input: &str) -> Result<Vec<(u32, u32)>, ParseError> {
+    Span::new(input)
         .trim()
-        .split('\n')
+        .lines()
         .map(|d| {
-            let mut iter = d.trim().split(" ").filter(|d2| !d2.is_empty());
-            let d1 = iter.next().unwrap();
-            let d1 = d1.parse::<u32>().unwrap();
-            let d2 = iter.next().unwrap();
-            let d2 = d2.parse::<u32>().unwrap();
-            (d1, d2)
+            let mut iter = d.split_whitespace();
+            match (iter.next(), iter.next(), iter.next()) {
+                (Some(d1), Some(d2), None) => Ok((d1.parse::<u32>()?, d2.parse::<u32>()?)),
+                _ => Err(d.trim().error("two location ids")),
+            }
         })
-        .collect::<Vec<_>>()
+        .collect()
 }
 
-pub fn day1_part1_solution(input: String) -> String {
-    let input = parse_input(input);
+#[aoc(year = 2024, day = 1, part = 1)]
+pub fn day1_part1_solution(input: &str) -> Result<Answer, AocError> {
+    let input = timing::parse(|| parse_input(input))?;
     let (mut alist, mut blist): (Vec<_>, Vec<_>) = input.into_iter().unzip();
     alist.sort();
     blist.sort();
@@ -26,11 +28,12 @@ pub fn day1_part1_solution(input: String) -> String {
         .zip(blist)
         .map(|(a, b)| a.abs_diff(b))
         .sum::<u32>();
-    ans.to_string()
+    Ok(ans.to_string())
 }
 
-pub fn day1_part2_solution(input: String) -> String {
-    let input = parse_input(input);
+#[aoc(year = 2024, day = 1, part = 2)]
+pub fn day1_part2_solution(input: &str) -> Result<Answer, AocError> {
+    let input = timing::parse(|| parse_input(input))?;
     let (alist, blist): (Vec<_>, Vec<_>) = input.into_iter().unzip();
 
     let mut bmap = HashMap::<u32, u32>::default();
@@ -56,7 +59,7 @@ pub fn day1_part2_solution(input: String) -> String {
         })
         .sum::<u32>();
 
-    ans.to_string()
+    Ok(ans.to_string())
 }
 
 #[cfg(test)]
@@ -72,15 +75,18 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        let parsed = parse_input(INPUT_STR.into());
+        let parsed = parse_input(INPUT_STR).unwrap();
         println!("parsed: {:?}", parsed);
-        let ans = day1_part1_solution(INPUT_STR.into());
+        let ans = day1_part1_solution(INPUT_STR).unwrap();
         assert_eq!(ans, "11");
+
+        assert!(parse_input("3 4 5").is_err());
+        assert!(day1_part1_solution("3 x").is_err());
     }
 
     #[test]
     fn test_part2() {
-        let ans = day1_part2_solution(INPUT_STR.into());
+        let ans = day1_part2_solution(INPUT_STR).unwrap();
         assert_eq!(ans, "31");
     }
 }
@@ -1,14 +1,11 @@
-fn parse_input(input: String) -> Vec<Vec<u32>> {
-    input
-        .trim()
-        .split('\n')
-        .map(|d| {
-            d.trim()
-                .split(" ")
-                .map(|d| d.parse::<u32>().unwrap())
-                .collect::<Vec<_>>()
-        })
-        .collect::<Vec<_>>()
+use common::{
+    aoc,
+    combinators::{self, integer, lines, words},
+    timing, Answer, AocError, ParseError,
+};
+
+fn parse_input(input: &str) -> Result<Vec<Vec<u32>>, ParseError> {
+    combinators::parse(input, lines(words(integer::<u32>)))
 }
 
 pub fn is_safe(level: &[u32]) -> bool {
@@ -21,17 +18,19 @@ pub fn is_safe(level: &[u32]) -> bool {
     })
 }
 
-pub fn day2_part1_solution(input: String) -> String {
-    let input = parse_input(input);
+#[aoc(year = 2024, day = 2, part = 1)]
+pub fn day2_part1_solution(input: &str) -> Result<Answer, AocError> {
+    let input = timing::parse(|| parse_input(input))?;
     let ans = input
         .into_iter()
         .map(|i| if is_safe(&i) { 1 } else { 0 })
         .sum::<u32>();
-    ans.to_string()
+    Ok(ans.to_string())
 }
 
-pub fn day2_part2_solution(input: String) -> String {
-    let input = parse_input(input);
+#[aoc(year = 2024, day = 2, part = 2)]
+pub fn day2_part2_solution(input: &str) -> Result<Answer, AocError> {
+    let input = timing::parse(|| parse_input(input))?;
     let ans = input
         .into_iter()
         .map(|mut i| {
@@ -49,7 +48,7 @@ pub fn day2_part2_solution(input: String) -> String {
             }
         })
         .sum::<u32>();
-    ans.to_string()
+    Ok(ans.to_string())
 }
 
 #[cfg(test)]
@@ -65,16 +64,16 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        let parsed = parse_input(INPUT_STR.into());
+        let parsed = parse_input(INPUT_STR).unwrap();
         println!("parsed: {:?}", parsed);
-        let ans = day2_part1_solution(INPUT_STR.into());
+        let ans = day2_part1_solution(INPUT_STR).unwrap();
         println!("Ans: {ans}");
         assert_eq!(ans, "2");
     }
 
     #[test]
     fn test_part2() {
-        let ans = day2_part2_solution(INPUT_STR.into());
+        let ans = day2_part2_solution(INPUT_STR).unwrap();
         println!("Ans: {ans}");
         assert_eq!(ans, "4");
     }
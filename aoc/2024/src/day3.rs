@@ -1,48 +1,60 @@
-pub fn day3_part1_solution(input: String) -> String {
-    let re = regex::Regex::new(r"mul\((\d{1,3}),(\d{1,3})\)").unwrap();
-    let ans = re
-        .captures_iter(&input)
+use common::{aoc, timing, Answer, AocError};
+
+enum Instruction {
+    Mul(u32, u32),
+    Do,
+    Dont,
+}
+
+/// The instructions hidden in the corrupted memory, everything else is
+/// skipped
+fn parse_instructions(input: &str) -> Vec<Instruction> {
+    let re = regex::Regex::new(r"mul\((\d{1,3}),(\d{1,3})\)|don't\(\)|do\(\)").unwrap();
+    re.captures_iter(input)
         .map(|capture| {
-            let a_str = capture.get(1).unwrap().as_str();
-            let b_str = capture.get(2).unwrap().as_str();
-            if (1..=3).contains(&a_str.len()) && (1..=3).contains(&b_str.len()) {
-                a_str.parse::<u32>().unwrap() * b_str.parse::<u32>().unwrap()
+            let identifier = capture.get(0).unwrap().as_str();
+            if identifier.starts_with("mul") {
+                // NOTE, The regex only matches 1 to 3 digits, parsing can't fail
+                let a = capture[1].parse::<u32>().unwrap();
+                let b = capture[2].parse::<u32>().unwrap();
+                Instruction::Mul(a, b)
+            } else if identifier.starts_with("don't") {
+                Instruction::Dont
             } else {
-                0
+                Instruction::Do
             }
         })
+        .collect()
+}
+
+#[aoc(year = 2024, day = 3, part = 1)]
+pub fn day3_part1_solution(input: &str) -> Result<Answer, AocError> {
+    let instructions = timing::parse(|| parse_instructions(input));
+    let ans = instructions
+        .iter()
+        .map(|instruction| match instruction {
+            Instruction::Mul(a, b) => a * b,
+            Instruction::Do | Instruction::Dont => 0,
+        })
         .sum::<u32>();
-    ans.to_string()
+    Ok(ans.to_string())
 }
 
-pub fn day3_part2_solution(input: String) -> String {
-    let re = regex::Regex::new(r"mul\((\d{1,3}),(\d{1,3})\)|don't\(\)|do\(\)").unwrap();
+#[aoc(year = 2024, day = 3, part = 2)]
+pub fn day3_part2_solution(input: &str) -> Result<Answer, AocError> {
+    let instructions = timing::parse(|| parse_instructions(input));
 
     let mut ans = 0;
     let mut process = true;
-    for capture in re.captures_iter(&input) {
-        let identifier = capture.get(0).unwrap().as_str();
-        if identifier.contains("mul") {
-            if !process {
-                continue;
-            }
-            let a_str = capture.get(1).unwrap().as_str();
-            let b_str = capture.get(2).unwrap().as_str();
-            let multiplied = if (1..=3).contains(&a_str.len()) && (1..=3).contains(&b_str.len()) {
-                a_str.parse::<u32>().unwrap() * b_str.parse::<u32>().unwrap()
-            } else {
-                0
-            };
-            ans += multiplied;
-        } else if identifier.contains("don't") {
-            process = false;
-        } else if identifier.contains("do") {
-            process = true;
-        } else {
-            unreachable!()
+    for instruction in instructions {
+        match instruction {
+            Instruction::Mul(a, b) if process => ans += a * b,
+            Instruction::Mul(..) => {}
+            Instruction::Do => process = true,
+            Instruction::Dont => process = false,
         }
     }
-    ans.to_string()
+    Ok(ans.to_string())
 }
 
 #[cfg(test)]
@@ -56,14 +68,14 @@ mod tests {
         "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
     #[test]
     fn test_part1() {
-        let ans = day3_part1_solution(INPUT_STR.into());
+        let ans = day3_part1_solution(INPUT_STR).unwrap();
         println!("Ans: {ans}");
         assert_eq!(ans, "161");
     }
 
     #[test]
     fn test_part2() {
-        let ans = day3_part2_solution(INPUT_STR2.into());
+        let ans = day3_part2_solution(INPUT_STR2).unwrap();
         println!("Ans: {ans}");
         assert_eq!(ans, "48");
     }
@@ -1,312 +1,84 @@
-pub fn parse_input(input: String) -> Vec<Vec<char>> {
-    input
-        .trim()
-        .split("\n")
-        .map(|xline| xline.trim().chars().collect::<Vec<_>>())
-        .collect()
+use common::{aoc, geom::Direction8, timing, Answer, AocError, Grid, ParseError, Point};
+
+pub fn parse_input(input: &str) -> Result<Grid<char>, ParseError> {
+    Grid::parse(input, "a letter", Some)
 }
 
 const XMAS: &[char] = &['X', 'M', 'A', 'S'];
 
 struct XmasGrid {
-    grid: Vec<Vec<char>>,
-    vertical_len: usize,
-    horizontal_len: usize,
+    grid: Grid<char>,
     xmas: &'static [char],
 }
 
 impl XmasGrid {
-    pub fn new(grid: Vec<Vec<char>>) -> Self {
-        let vertical_len = grid.len();
-        let horizontal_len = grid[0].len();
-        Self {
-            grid,
-            vertical_len,
-            horizontal_len,
-            xmas: XMAS,
-        }
-    }
-
-    pub fn compute_all_directions(&self, point: (usize, usize)) -> u32 {
-        let mut count = 0;
-        count += self.east(point) as u32;
-        count += self.west(point) as u32;
-        count += self.north(point) as u32;
-        count += self.south(point) as u32;
-        count += self.northeast(point) as u32;
-        count += self.northwest(point) as u32;
-        count += self.southeast(point) as u32;
-        count += self.southwest(point) as u32;
-        count
-    }
-
-    // XMAS
-    // east
-    fn east(&self, point: (usize, usize)) -> bool {
-        let ymax = point.1 + 3;
-        if ymax >= self.horizontal_len {
-            return false;
-        }
-        let c = &self.grid[point.0][point.1..=ymax];
-        c == self.xmas
-    }
-
-    // SAMX
-    // west
-    fn west(&self, point: (usize, usize)) -> bool {
-        let ymin = point.1.checked_sub(3);
-        let ymin = match ymin {
-            Some(ymin) => ymin,
-            None => return false,
-        };
-
-        let c = &self.grid[point.0][ymin..=point.1];
-        self.xmas.iter().rev().eq(c.iter())
-    }
-
-    // X
-    // M
-    // A
-    // S
-    fn south(&self, point: (usize, usize)) -> bool {
-        let xmax = point.0 + 3;
-        if xmax >= self.vertical_len {
-            return false;
-        }
-        let mut c = ['0'; 4];
-        for (counter, x) in (point.0..=xmax).enumerate() {
-            c[counter] = self.grid[x][point.1];
-        }
-        c == self.xmas
-    }
-
-    // S
-    // A
-    // M
-    // X
-    fn north(&self, point: (usize, usize)) -> bool {
-        let xmin = point.0.checked_sub(3);
-        let xmin = match xmin {
-            Some(xmin) => xmin,
-            None => return false,
-        };
-        let mut c = ['0'; 4];
-        for (counter, x) in (xmin..=point.0).rev().enumerate() {
-            c[counter] = self.grid[x][point.1];
-        }
-        c == self.xmas
-    }
-
-    //    S
-    //   A
-    //  M
-    // X
-    fn northeast(&self, point: (usize, usize)) -> bool {
-        let xmin = point.0.checked_sub(3);
-        let xmin = match xmin {
-            Some(xmin) => xmin,
-            None => return false,
-        };
-        let ymax = point.1 + 3;
-        if ymax >= self.horizontal_len {
-            return false;
-        }
-
-        let mut c = ['0'; 4];
-        for (counter, (x, y)) in (xmin..=point.0).rev().zip(point.1..=ymax).enumerate() {
-            c[counter] = self.grid[x][y];
-        }
-        c == self.xmas
+    pub fn new(grid: Grid<char>) -> Self {
+        Self { grid, xmas: XMAS }
     }
 
-    // S
-    //  A
-    //   M
-    //    X
-    fn northwest(&self, point: (usize, usize)) -> bool {
-        let xmin = point.0.checked_sub(3);
-        let xmin = match xmin {
-            Some(xmin) => xmin,
-            None => return false,
-        };
-        let ymin = point.1.checked_sub(3);
-        let ymin = match ymin {
-            Some(ymin) => ymin,
-            None => return false,
-        };
-        let mut c = ['0'; 4];
-        for (counter, (x, y)) in (xmin..=point.0)
-            .rev()
-            .zip((ymin..=point.1).rev())
-            .enumerate()
-        {
-            c[counter] = self.grid[x][y];
-        }
-        c == self.xmas
-    }
-
-    // X
-    //  M
-    //   A
-    //    S
-    fn southeast(&self, point: (usize, usize)) -> bool {
-        let xmax = point.0 + 3;
-        if xmax >= self.vertical_len {
-            return false;
-        }
-        let ymax = point.1 + 3;
-        if ymax >= self.horizontal_len {
-            return false;
-        }
-
-        let mut c = ['0'; 4];
-        for (counter, (x, y)) in (point.0..=xmax).zip(point.1..=ymax).enumerate() {
-            c[counter] = self.grid[x][y];
-        }
-        c == self.xmas
-    }
-
-    //    X
-    //   M
-    //  A
-    // S
-    fn southwest(&self, point: (usize, usize)) -> bool {
-        let xmax = point.0 + 3;
-        if xmax >= self.vertical_len {
-            return false;
-        }
-        let ymin = point.1.checked_sub(3);
-        let ymin = match ymin {
-            Some(ymin) => ymin,
-            None => return false,
-        };
-
-        let mut c = ['0'; 4];
-        for (counter, (x, y)) in (point.0..=xmax).zip((ymin..=point.1).rev()).enumerate() {
-            c[counter] = self.grid[x][y];
-        }
-        c == self.xmas
+    /// Counts the directions (rows, columns and diagonals, both ways) in which
+    /// XMAS is spelled out starting from point
+    pub fn compute_all_directions(&self, point: Point) -> u32 {
+        Direction8::ALL
+            .iter()
+            .filter(|direction| {
+                let word = self
+                    .grid
+                    .line(point, direction.delta())
+                    .map(|p| &self.grid[p]);
+                word.take(self.xmas.len()).eq(self.xmas.iter())
+            })
+            .count() as u32
     }
 }
 
-pub fn day4_part1_solution(input: String) -> String {
-    let input = parse_input(input);
-    let xlist = input
-        .iter()
-        .enumerate()
-        .flat_map(|(x, xline)| {
-            //
-            xline.iter().enumerate().filter_map(move |(y, ychar)| {
-                //
-                if *ychar == 'X' {
-                    Some((x, y))
-                } else {
-                    None
-                }
-            })
-        })
-        .collect::<Vec<_>>();
-
+#[aoc(year = 2024, day = 4, part = 1)]
+pub fn day4_part1_solution(input: &str) -> Result<Answer, AocError> {
+    let input = timing::parse(|| parse_input(input))?;
     let grid = XmasGrid::new(input);
-    let ans = xlist
-        .into_iter()
+    let ans = grid
+        .grid
+        .find_all(&'X')
         .map(|point| grid.compute_all_directions(point))
         .sum::<u32>();
-    ans.to_string()
+    Ok(ans.to_string())
 }
 
 struct XShapedMasGrid {
-    grid: Vec<Vec<char>>,
-    vertical_len: usize,
-    horizontal_len: usize,
+    grid: Grid<char>,
 }
 
 impl XShapedMasGrid {
-    pub fn new(grid: Vec<Vec<char>>) -> Self {
-        let vertical_len = grid.len();
-        let horizontal_len = grid[0].len();
-        Self {
-            grid,
-            vertical_len,
-            horizontal_len,
-        }
+    pub fn new(grid: Grid<char>) -> Self {
+        Self { grid }
     }
 
     // M . M
     // . A .
     // S . S
     // point is coordinates of A
-    pub fn is_x_mas(&self, point: (usize, usize)) -> bool {
-        let xmax = point.0 + 1;
-        if xmax >= self.vertical_len {
-            return false;
-        }
-        let xmin = point.0.checked_sub(1);
-        let xmin = match xmin {
-            Some(xmin) => xmin,
-            None => return false,
+    pub fn is_x_mas(&self, point: Point) -> bool {
+        let is_mas = |from: (isize, isize), to: (isize, isize)| {
+            let from = self.grid.step(point, from).map(|p| self.grid[p]);
+            let to = self.grid.step(point, to).map(|p| self.grid[p]);
+            matches!((from, to), (Some('M'), Some('S')) | (Some('S'), Some('M')))
         };
-        let ymax = point.1 + 1;
-        if ymax >= self.horizontal_len {
-            return false;
-        }
-        let ymin = point.1.checked_sub(1);
-        let ymin = match ymin {
-            Some(ymin) => ymin,
-            None => return false,
-        };
-
-        let top_left_to_bottom_right = {
-            // cross from top left to bottom right
-            let mut c = ['0'; 3];
-            for (counter, (x, y)) in (xmin..=xmax).zip(ymin..=ymax).enumerate() {
-                c[counter] = self.grid[x][y];
-            }
-
-            c == ['M', 'A', 'S'] || c == ['S', 'A', 'M']
-        };
-
-        let bottom_left_to_top_right = {
-            // cross from bottom left to top right
-            let mut c = ['0'; 3];
-            for (counter, (x, y)) in (xmin..=xmax).rev().zip(ymin..=ymax).enumerate() {
-                c[counter] = self.grid[x][y];
-            }
 
-            c == ['M', 'A', 'S'] || c == ['S', 'A', 'M']
-        };
-
-        top_left_to_bottom_right && bottom_left_to_top_right
+        // cross from top left to bottom right, and from bottom left to top right
+        is_mas((-1, -1), (1, 1)) && is_mas((-1, 1), (1, -1))
     }
 }
 
-pub fn day4_part2_solution(input: String) -> String {
-    let input = parse_input(input);
-    let alist = input
-        .iter()
-        .enumerate()
-        .flat_map(|(x, xline)| {
-            //
-            xline.iter().enumerate().filter_map(move |(y, ychar)| {
-                //
-                if *ychar == 'A' {
-                    Some((x, y))
-                } else {
-                    None
-                }
-            })
-        })
-        .collect::<Vec<_>>();
-
+#[aoc(year = 2024, day = 4, part = 2)]
+pub fn day4_part2_solution(input: &str) -> Result<Answer, AocError> {
+    let input = timing::parse(|| parse_input(input))?;
     let grid = XShapedMasGrid::new(input);
-    let ans = alist
-        .into_iter()
-        .map(|apoint| {
-            //
-            grid.is_x_mas(apoint) as u32
-        })
+    let ans = grid
+        .grid
+        .find_all(&'A')
+        .map(|apoint| grid.is_x_mas(apoint) as u32)
         .sum::<u32>();
-    ans.to_string()
+    Ok(ans.to_string())
 }
 
 #[cfg(test)]
@@ -326,14 +98,14 @@ MXMXAXMASX";
 
     #[test]
     fn test_part1() {
-        let ans = day4_part1_solution(INPUT_STR.into());
+        let ans = day4_part1_solution(INPUT_STR).unwrap();
         println!("Ans: {ans}");
         assert_eq!(ans, "18");
     }
 
     #[test]
     fn test_part2() {
-        let ans = day4_part2_solution(INPUT_STR.into());
+        let ans = day4_part2_solution(INPUT_STR).unwrap();
         println!("Ans: {ans}");
         assert_eq!(ans, "9");
     }
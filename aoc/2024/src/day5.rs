@@ -1,53 +1,120 @@
-use std::collections::{hash_map::Entry, HashMap, HashSet};
+use std::{
+    collections::{hash_map::Entry, HashMap, HashSet},
+    fmt,
+};
+
+use common::{
+    aoc,
+    combinators::{self, integer, lines, pair, separated},
+    diagnostics,
+    graph::{CycleError, Graph},
+    timing, Answer, AocError, ParseError,
+};
 
-// pub type Orderings = HashMap<u32, HashSet<u32>>;
 pub type Sequences = Vec<Vec<u32>>;
 
+/// `X|Y` rules, page X must come before page Y whenever both are in an
+/// update
 pub struct Orderings(HashMap<u32, HashSet<u32>>);
+
+/// An update breaks the rule `before|after`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Violation {
+    pub before: u32,
+    pub after: u32,
+    /// Where `after` and `before` are in the update, `after` comes first
+    pub positions: (usize, usize),
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}|{} is broken, {} is page {} and {} is page {}",
+            self.before,
+            self.after,
+            self.after,
+            self.positions.0 + 1,
+            self.before,
+            self.positions.1 + 1
+        )
+    }
+}
+
+/// Why an update can't be put in order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReorderError {
+    /// The page appears more than once, the update has no single middle page
+    DuplicatePage(u32),
+    Cycle(CycleError<u32>),
+}
+
+impl fmt::Display for ReorderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReorderError::DuplicatePage(page) => write!(f, "page {} appears twice", page),
+            ReorderError::Cycle(e) => e.fmt(f),
+        }
+    }
+}
+
 impl Orderings {
+    fn must_precede(&self, before: u32, after: u32) -> bool {
+        self.0.get(&before).is_some_and(|a| a.contains(&after))
+    }
+
+    /// The first rule `sequence` breaks, pages that no rule mentions are
+    /// never out of order
+    pub fn violation(&self, sequence: &[u32]) -> Option<Violation> {
+        (0..sequence.len()).find_map(|i| {
+            (i + 1..sequence.len()).find_map(|j| {
+                self.must_precede(sequence[j], sequence[i])
+                    .then_some(Violation {
+                        before: sequence[j],
+                        after: sequence[i],
+                        positions: (i, j),
+                    })
+            })
+        })
+    }
+
     pub fn is_ordered(&self, sequence: &[u32]) -> bool {
-        for index in 0..sequence.len() - 1 {
-            let preceding = sequence[index];
-            let following = &sequence[index + 1..];
-            let orderings = self
-                .0
-                .get(&preceding)
-                .unwrap_or_else(|| panic!("Must contain a ordering key {preceding}"));
-            let is_ordered = following.iter().all(|f| orderings.contains(f));
-            if !is_ordered {
-                return false;
+        self.violation(sequence).is_none()
+    }
+
+    /// The pages of `sequence` sorted by the rules between them only, pages
+    /// no rule orders keep the order of the update as far as possible
+    ///
+    /// Rules between pages that aren't in the update are ignored, the whole
+    /// set of rules is usually cyclic
+    pub fn reorder(&self, sequence: &[u32]) -> Result<Vec<u32>, ReorderError> {
+        let mut graph = Graph::new();
+        let mut seen = HashSet::new();
+        for &page in sequence {
+            if !seen.insert(page) {
+                return Err(ReorderError::DuplicatePage(page));
             }
+            graph.add_node(page);
         }
-        true
-    }
-
-    fn reorder(&self, sequence: &mut [u32]) {
-        for index in 0..sequence.len() - 1 {
-            loop {
-                let current = sequence[index];
-                let following = &sequence[index + 1..];
-                let orderings = self
-                    .0
-                    .get(&current)
-                    .unwrap_or_else(|| panic!("Must contain a ordering key {current}"));
-                let is_ordered = following.iter().all(|f| orderings.contains(f));
-                if !is_ordered {
-                    sequence[index..].rotate_left(1);
-                } else {
-                    break;
+        for &before in sequence {
+            for &after in sequence {
+                if self.must_precede(before, after) {
+                    graph.add_edge(before, after);
                 }
             }
         }
+        graph.topological_sort().map_err(ReorderError::Cycle)
     }
 }
 
-fn parse_input(input: String) -> (Orderings, Sequences) {
+fn parse_input(input: &str) -> Result<(Orderings, Sequences), ParseError> {
+    // NOTE, `X|Y` ordering rules, a blank line, then comma separated updates
+    let rules = lines(pair(integer::<u32>, "|", integer::<u32>));
+    let updates = lines(separated(",", integer::<u32>));
+    let (rules, sequences) = combinators::parse(input, pair(rules, "\n\n", updates))?;
+
     let mut orderings: HashMap<u32, HashSet<u32>> = HashMap::default();
-    let mut data = input.trim().split("\n\n");
-    data.next().unwrap().split("\n").for_each(|data| {
-        let mut iter = data.split("|");
-        let before = iter.next().unwrap().parse::<u32>().unwrap();
-        let after = iter.next().unwrap().parse::<u32>().unwrap();
+    for (before, after) in rules {
         match orderings.entry(before) {
             Entry::Occupied(mut oe) => {
                 oe.get_mut().insert(after);
@@ -65,24 +132,14 @@ fn parse_input(input: String) -> (Orderings, Sequences) {
                 ve.insert(HashSet::new());
             }
         }
-    });
-
-    let sequences = data
-        .next()
-        .unwrap()
-        .split("\n")
-        .map(|line| {
-            line.split(",")
-                .map(|iline| iline.parse::<u32>().unwrap())
-                .collect::<Vec<_>>()
-        })
-        .collect::<Vec<_>>();
+    }
 
-    (Orderings(orderings), sequences)
+    Ok((Orderings(orderings), sequences))
 }
 
-pub fn day5_part1_solution(input: String) -> String {
-    let (orderings, sequences) = parse_input(input);
+#[aoc(year = 2024, day = 5, part = 1)]
+pub fn day5_part1_solution(input: &str) -> Result<Answer, AocError> {
+    let (orderings, sequences) = timing::parse(|| parse_input(input))?;
     let mut ans = 0;
     for s in sequences {
         let is_ordered = orderings.is_ordered(&s);
@@ -92,22 +149,30 @@ pub fn day5_part1_solution(input: String) -> String {
         let num = s[s.len() / 2];
         ans += num;
     }
-    ans.to_string()
+    Ok(ans.to_string())
 }
 
-pub fn day5_part2_solution(input: String) -> String {
-    let (orderings, sequences) = parse_input(input);
+#[aoc(year = 2024, day = 5, part = 2)]
+pub fn day5_part2_solution(input: &str) -> Result<Answer, AocError> {
+    let (orderings, sequences) = timing::parse(|| parse_input(input))?;
     let mut ans = 0;
-    for mut s in sequences {
-        let is_ordered = orderings.is_ordered(&s);
-        if is_ordered {
+    for (index, s) in sequences.iter().enumerate() {
+        let Some(violation) = orderings.violation(s) else {
             continue;
-        }
-        orderings.reorder(&mut s);
+        };
+        diagnostics::note(format!("update {}, {}", index + 1, violation));
+        let s = orderings.reorder(s).map_err(|e| {
+            AocError::Unsolvable(format!(
+                "update {} can't be ordered, {}, {}",
+                index + 1,
+                violation,
+                e
+            ))
+        })?;
         let num = s[s.len() / 2];
         ans += num;
     }
-    ans.to_string()
+    Ok(ans.to_string())
 }
 
 #[cfg(test)]
@@ -145,15 +210,106 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        let ans = day5_part1_solution(INPUT_STR.into());
+        let ans = day5_part1_solution(INPUT_STR).unwrap();
         println!("Ans: {ans}");
         assert_eq!(ans, "143");
     }
 
     #[test]
     fn test_part2() {
-        let ans = day5_part2_solution(INPUT_STR.into());
+        let ans = day5_part2_solution(INPUT_STR).unwrap();
         println!("Ans: {ans}");
         assert_eq!(ans, "123");
     }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = parse_input("47|53\n97 13\n\n75,47").err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 1: expected `|`, found \"97 13\""
+        );
+
+        let error = parse_input("47|53\n\n75,x,61").err().unwrap();
+        assert_eq!((error.line, error.column), (3, 4));
+    }
+
+    #[test]
+    fn test_violations() {
+        let (orderings, sequences) = parse_input(INPUT_STR).unwrap();
+        let violations = sequences
+            .iter()
+            .map(|s| orderings.violation(s))
+            .collect::<Vec<_>>();
+        assert_eq!(violations[..3], [None, None, None]);
+        let violation = violations[3].unwrap();
+        assert_eq!(
+            violation,
+            Violation {
+                before: 97,
+                after: 75,
+                positions: (0, 1)
+            }
+        );
+        assert_eq!(
+            violation.to_string(),
+            "97|75 is broken, 75 is page 1 and 97 is page 2"
+        );
+        assert_eq!(
+            orderings.reorder(&sequences[3]),
+            Ok(vec![97, 75, 47, 61, 53])
+        );
+        assert_eq!(
+            orderings.reorder(&sequences[5]),
+            Ok(vec![97, 75, 47, 29, 13])
+        );
+    }
+
+    #[test]
+    fn test_unknown_pages_and_cycles() {
+        let (orderings, _) = parse_input("1|2\n2|3\n3|1\n\n1,2").unwrap();
+        assert!(orderings.is_ordered(&[7, 1, 8, 2, 9]));
+        assert!(!orderings.is_ordered(&[2, 7, 1]));
+        assert_eq!(orderings.reorder(&[2, 7, 1]), Ok(vec![7, 1, 2]));
+
+        assert_eq!(
+            orderings.reorder(&[4, 3, 1, 2]),
+            Err(ReorderError::Cycle(CycleError {
+                nodes: vec![3, 1, 2]
+            }))
+        );
+        let error = day5_part2_solution("1|2\n2|3\n3|1\n\n1,2\n3,2,1").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "no answer, update 2 can't be ordered, 2|3 is broken, 3 is page 1 and 2 is page 2, cycle between [3, 2, 1]"
+        );
+    }
+
+    #[test]
+    fn test_duplicate_pages() {
+        let (orderings, _) = parse_input(INPUT_STR).unwrap();
+        assert_eq!(
+            orderings.reorder(&[47, 75, 47]),
+            Err(ReorderError::DuplicatePage(47))
+        );
+        let error = day5_part2_solution("47|53\n\n53,47,53").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "no answer, update 1 can't be ordered, 47|53 is broken, 53 is page 1 and 47 is page 2, page 53 appears twice"
+        );
+    }
+
+    #[test]
+    fn test_notes() {
+        diagnostics::take_notes();
+        day5_part2_solution(INPUT_STR).unwrap();
+        assert_eq!(
+            diagnostics::take_notes(),
+            [
+                "update 4, 97|75 is broken, 75 is page 1 and 97 is page 2",
+                "update 5, 29|13 is broken, 13 is page 2 and 29 is page 3",
+                "update 6, 75|13 is broken, 13 is page 2 and 75 is page 3",
+            ]
+        );
+    }
 }
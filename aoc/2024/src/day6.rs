@@ -1,206 +1,184 @@
 use std::collections::HashSet;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum GuardAlignment {
-    Up,
-    Down,
-    Left,
-    Right,
+use common::{
+    aoc,
+    geom::Direction4,
+    timing, visualize,
+    visualize::{Cell, Color, Frame, Render},
+    Answer, AocError, Grid, ParseError, Point, Simulation, Span,
+};
+
+fn guard_alignment(c: char) -> Option<Direction4> {
+    match c {
+        '^' => Some(Direction4::Up),
+        'v' => Some(Direction4::Down),
+        '<' => Some(Direction4::Left),
+        '>' => Some(Direction4::Right),
+        _ => None,
+    }
 }
 
+#[derive(Clone)]
 struct Map {
     // constants
-    grid_vertical_length: usize,
-    grid_horizontal_length: usize,
-    obstacles: HashSet<(usize, usize)>,
+    obstacles: Grid<bool>,
 
     // state
-    guard_position: (usize, usize),
-    guard_alignment: GuardAlignment,
+    guard_position: Point,
+    guard_alignment: Direction4,
 }
 
 impl Map {
-    pub fn has_cycle(&mut self) -> bool {
-        let mut has_cycle = false;
-        let mut traversed_points = HashSet::new();
-        // traversed_points.reserve(1000);
-        while self.next().is_some() {
-            let current = (self.guard_position, self.guard_alignment);
-            if traversed_points.contains(&current) {
-                has_cycle = true;
-                break;
-            } else {
-                traversed_points.insert(current);
-            }
-        }
-        has_cycle
+    /// The guard walks in a loop instead of leaving the map
+    pub fn has_cycle(&self) -> bool {
+        self.find_cycle().is_some()
     }
+}
 
-    fn try_up(&mut self) -> Option<()> {
-        let xdir = self.guard_position.0;
-        let next_xdir = match xdir.checked_sub(1) {
-            Some(next_xdir) => next_xdir,
-            None => return None,
+impl Simulation for Map {
+    type State = (Point, Direction4);
+
+    /// Either turns in front of an obstacle or moves one step, `false` once the
+    /// guard would leave the map
+    fn step(&mut self) -> bool {
+        let Some(next_position) = self
+            .obstacles
+            .step(self.guard_position, self.guard_alignment.delta())
+        else {
+            return false;
         };
 
         // Check if there is an obstacle
-        if self.obstacles.contains(&(next_xdir, self.guard_position.1)) {
-            self.guard_alignment = GuardAlignment::Right;
+        if self.obstacles[next_position] {
+            self.guard_alignment = self.guard_alignment.turn_right();
         } else {
             // Move
-            self.guard_position.0 = next_xdir;
+            self.guard_position = next_position;
         }
+        true
+    }
 
-        Some(())
+    fn state(&self) -> Self::State {
+        (self.guard_position, self.guard_alignment)
     }
+}
 
-    fn try_down(&mut self) -> Option<()> {
-        let xdir = self.guard_position.0;
-        let next_xdir = xdir + 1;
-        if next_xdir >= self.grid_vertical_length {
-            return None;
-        }
+/// The guard's walk along with every position it has been on so far
+struct GuardWalk {
+    map: Map,
+    visited: Grid<bool>,
+}
 
-        // Check if there is an obstacle
-        if self.obstacles.contains(&(next_xdir, self.guard_position.1)) {
-            self.guard_alignment = GuardAlignment::Left;
-        } else {
-            // Move
-            self.guard_position.0 = next_xdir;
-        }
+impl GuardWalk {
+    fn new(map: Map) -> Self {
+        let mut visited = map.obstacles.map(|_| false);
+        visited[map.guard_position] = true;
+        Self { map, visited }
+    }
 
-        Some(())
+    fn count_visited(&self) -> usize {
+        self.visited.find_all(&true).count()
     }
+}
 
-    fn try_left(&mut self) -> Option<()> {
-        let ydir = self.guard_position.1;
-        let next_ydir = match ydir.checked_sub(1) {
-            Some(next_ydir) => next_ydir,
-            None => return None,
-        };
+impl Simulation for GuardWalk {
+    // NOTE, The visited positions don't change where the guard goes next
+    type State = <Map as Simulation>::State;
 
-        // Check if there is an obstacle
-        if self.obstacles.contains(&(self.guard_position.0, next_ydir)) {
-            self.guard_alignment = GuardAlignment::Up;
-        } else {
-            // Move
-            self.guard_position.1 = next_ydir;
+    fn step(&mut self) -> bool {
+        if !self.map.step() {
+            return false;
         }
-
-        Some(())
+        self.visited[self.map.guard_position] = true;
+        true
     }
 
-    fn try_right(&mut self) -> Option<()> {
-        let ydir = self.guard_position.1;
-        let next_ydir = ydir + 1;
-        if next_ydir >= self.grid_horizontal_length {
-            return None;
-        }
+    fn state(&self) -> Self::State {
+        self.map.state()
+    }
+}
 
-        if self.obstacles.contains(&(self.guard_position.0, next_ydir)) {
-            self.guard_alignment = GuardAlignment::Down;
-        } else {
-            self.guard_position.1 = next_ydir;
+impl Render for GuardWalk {
+    fn render(&self) -> Frame {
+        let mut frame = self.map.obstacles.map(|&obstacle| {
+            if obstacle {
+                Cell::new('#').bold()
+            } else {
+                Cell::new('.')
+            }
+        });
+        for point in self.visited.find_all(&true) {
+            frame[point] = Cell::colored('X', Color::Green);
         }
+        let guard = match self.map.guard_alignment {
+            Direction4::Up => '^',
+            Direction4::Right => '>',
+            Direction4::Down => 'v',
+            Direction4::Left => '<',
+        };
+        frame[self.map.guard_position] = Cell::colored(guard, Color::Red).bold();
+        frame
+    }
 
-        Some(())
+    fn caption(&self) -> String {
+        format!("{} positions visited", self.count_visited())
     }
 }
 
-impl Iterator for Map {
-    type Item = ();
-    fn next(&mut self) -> Option<Self::Item> {
-        match self.guard_alignment {
-            GuardAlignment::Up => self.try_up(),
-            GuardAlignment::Down => self.try_down(),
-            GuardAlignment::Left => self.try_left(),
-            GuardAlignment::Right => self.try_right(),
-        }
-    }
+#[visualize(year = 2024, day = 6)]
+fn guard_walk(input: &str) -> Result<GuardWalk, AocError> {
+    Ok(GuardWalk::new(parse_input(input)?))
 }
 
-fn parse_input(input: String) -> Map {
-    let input = input
-        .trim()
-        .split("\n")
-        .map(|line| line.chars().collect::<Vec<char>>())
-        .collect::<Vec<_>>();
-
-    let grid_vertical_length = input.len();
-    let grid_horizontal_length = input[0].len();
-
-    let mut guard_position = None;
-    let mut guard_alignment = None;
-    let mut obstacles = HashSet::new();
-    input.into_iter().enumerate().for_each(|(x, line)| {
-        //
-        line.into_iter().enumerate().for_each(|(y, c)| {
-            //
-            match c {
-                '^' => {
-                    guard_position = Some((x, y));
-                    guard_alignment = Some(GuardAlignment::Up)
-                }
-                'v' => {
-                    guard_position = Some((x, y));
-                    guard_alignment = Some(GuardAlignment::Down)
-                }
-                '<' => {
-                    guard_position = Some((x, y));
-                    guard_alignment = Some(GuardAlignment::Left)
-                }
-                '>' => {
-                    guard_position = Some((x, y));
-                    guard_alignment = Some(GuardAlignment::Right)
-                }
-                '#' => {
-                    obstacles.insert((x, y));
-                }
-                _ => {}
-            }
-        })
-    });
-    let guard_position = guard_position.unwrap();
-    let guard_alignment = guard_alignment.unwrap();
-
-    Map {
-        grid_vertical_length,
-        grid_horizontal_length,
-        obstacles,
+fn parse_input(input: &str) -> Result<Map, ParseError> {
+    let end = Span::new(input).trim().end();
+    let grid = Grid::parse(input, "`.`, `#` or a guard (`^`, `v`, `<` or `>`)", |c| {
+        matches!(c, '.' | '#' | '^' | 'v' | '<' | '>').then_some(c)
+    })?;
+
+    let guard = grid
+        .iter()
+        .find_map(|(p, &c)| Some((p, guard_alignment(c)?)));
+    let Some((guard_position, guard_alignment)) = guard else {
+        return Err(end.error("a guard (`^`, `v`, `<` or `>`)"));
+    };
+
+    Ok(Map {
+        obstacles: grid.map(|&c| c == '#'),
         guard_position,
         guard_alignment,
-    }
+    })
 }
 
-pub fn day6_part1_solution(input: String) -> String {
-    let mut input = parse_input(input);
+#[aoc(year = 2024, day = 6, part = 1)]
+pub fn day6_part1_solution(input: &str) -> Result<Answer, AocError> {
+    let input = timing::parse(|| parse_input(input))?;
 
-    let mut pos = HashSet::from([input.guard_position]);
-    while input.next().is_some() {
-        pos.insert(input.guard_position);
-    }
+    let mut walk = GuardWalk::new(input);
+    while walk.step() {}
 
-    let ans = pos.len();
-    ans.to_string()
+    let ans = walk.count_visited();
+    Ok(ans.to_string())
 }
 
-pub fn day6_part2_solution(input: String) -> String {
-    let mut input = parse_input(input);
+#[aoc(year = 2024, day = 6, part = 2)]
+pub fn day6_part2_solution(input: &str) -> Result<Answer, AocError> {
+    let mut input = timing::parse(|| parse_input(input))?;
 
     let start_pos = input.guard_position;
     let start_align = input.guard_alignment;
 
     // Get path traversed by guard
     let mut full_path_as_points = HashSet::new();
-    while input.next().is_some() {
+    while input.step() {
         full_path_as_points.insert(input.guard_position);
     }
 
     let mut obstacles_with_cycles = HashSet::new();
     for obstacle in full_path_as_points {
-        assert!(!input.obstacles.contains(&obstacle));
+        assert!(!input.obstacles[obstacle]);
         // Set Map for simulation
-        input.obstacles.insert(obstacle);
+        input.obstacles[obstacle] = true;
         input.guard_position = start_pos;
         input.guard_alignment = start_align;
 
@@ -211,10 +189,10 @@ pub fn day6_part2_solution(input: String) -> String {
         }
 
         // Reset Map
-        input.obstacles.remove(&obstacle);
+        input.obstacles[obstacle] = false;
     }
 
-    obstacles_with_cycles.len().to_string()
+    Ok(obstacles_with_cycles.len().to_string())
 }
 
 #[cfg(test)]
@@ -234,14 +212,25 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        let ans = day6_part1_solution(INPUT_STR.into());
+        let ans = day6_part1_solution(INPUT_STR).unwrap();
         println!("Ans: {ans}");
         assert_eq!(ans, "41");
     }
 
+    #[test]
+    fn test_guard_walk_render() {
+        let mut walk = GuardWalk::new(parse_input(INPUT_STR).unwrap());
+        assert_eq!(walk.render().to_string().lines().nth(6), Some(".#..^....."));
+        walk.run(6);
+        let frame = walk.render().to_string();
+        assert_eq!(frame.lines().next(), Some("....#....."));
+        assert_eq!(frame.lines().nth(1), Some("....>....#"));
+        assert_eq!(walk.caption(), "6 positions visited");
+    }
+
     #[test]
     fn test_part2() {
-        let ans = day6_part2_solution(INPUT_STR.into());
+        let ans = day6_part2_solution(INPUT_STR).unwrap();
         println!("Ans: {ans}");
         assert_eq!(ans, "6");
     }
@@ -0,0 +1,216 @@
+//! Small parser combinators for the usual puzzle input formats
+//!
+//! Every parser takes a whole [`Span`] and either turns all of it into a
+//! value or fails with a [`ParseError`] pointing into the input. Parsers nest
+//! the same way the input does
+//!
+//! ```ignore
+//! // 0,9 -> 5,9
+//! let segments = combinators::parse(input, lines(pair(point, "->", point)))?;
+//! ```
+
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    hash::Hash,
+    str::FromStr,
+};
+
+use crate::{ParseError, Span};
+
+/// Anything that turns a span of the input into a `T`
+pub trait Parser<'a, T>: Fn(Span<'a>) -> Result<T, ParseError> {}
+
+impl<'a, T, F: Fn(Span<'a>) -> Result<T, ParseError>> Parser<'a, T> for F {}
+
+/// Runs `parser` on the whole `input`, ignoring surrounding whitespace
+pub fn parse<'a, T>(input: &'a str, parser: impl Parser<'a, T>) -> Result<T, ParseError> {
+    parser(Span::new(input).trim())
+}
+
+/// An integer, or anything else that implements [`FromStr`]
+pub fn integer<'a, T: FromStr>(input: Span<'a>) -> Result<T, ParseError> {
+    input.trim().parse::<T>()
+}
+
+/// The text itself, for names and templates
+pub fn text<'a>(input: Span<'a>) -> Result<&'a str, ParseError> {
+    Ok(input.trim().as_str())
+}
+
+/// Applies `f` to the value of `parser`
+pub fn map<'a, T, U>(parser: impl Parser<'a, T>, f: impl Fn(T) -> U) -> impl Parser<'a, U> {
+    move |input| parser(input).map(&f)
+}
+
+/// Items separated by `separator`, e.g. `16,1,2,0,4`
+pub fn separated<'a, T>(separator: &'a str, item: impl Parser<'a, T>) -> impl Parser<'a, Vec<T>> {
+    move |input: Span<'a>| input.split(separator).map(|s| item(s.trim())).collect()
+}
+
+/// Items separated by any amount of whitespace, e.g. `41 48 83 86 17`
+pub fn words<'a, T>(item: impl Parser<'a, T>) -> impl Parser<'a, Vec<T>> {
+    move |input: Span<'a>| input.split_whitespace().map(&item).collect()
+}
+
+/// One item per line
+pub fn lines<'a, T>(item: impl Parser<'a, T>) -> impl Parser<'a, Vec<T>> {
+    move |input: Span<'a>| input.lines().map(|l| item(l.trim())).collect()
+}
+
+/// One item per block of lines, blocks are separated by blank lines
+pub fn sections<'a, T>(item: impl Parser<'a, T>) -> impl Parser<'a, Vec<T>> {
+    move |input: Span<'a>| input.sections().map(&item).collect()
+}
+
+/// Two values around the first `separator`, e.g. `x=5` or `47|53`
+pub fn pair<'a, A, B>(
+    first: impl Parser<'a, A>,
+    separator: &'a str,
+    second: impl Parser<'a, B>,
+) -> impl Parser<'a, (A, B)> {
+    move |input: Span<'a>| {
+        let (a, b) = input.split_once(separator)?;
+        Ok((first(a.trim())?, second(b.trim())?))
+    }
+}
+
+/// One `key <separator> value` pair per line, e.g. `CH -> B`, a key may only
+/// be given once
+pub fn key_values<'a, K: Eq + Hash, V>(
+    key: impl Parser<'a, K>,
+    separator: &'a str,
+    value: impl Parser<'a, V>,
+) -> impl Parser<'a, HashMap<K, V>> {
+    move |input: Span<'a>| {
+        let mut entries = HashMap::new();
+        for line in input.lines() {
+            let (k, v) = line.trim().split_once(separator)?;
+            let k = k.trim();
+            match entries.entry(key(k)?) {
+                Entry::Occupied(_) => return Err(k.error("a key that isn't defined yet")),
+                Entry::Vacant(entry) => {
+                    entry.insert(value(v.trim())?);
+                }
+            }
+        }
+        Ok(entries)
+    }
+}
+
+/// `item` after a literal `prefix`, e.g. `fold along y=7`
+pub fn prefixed<'a, T>(prefix: &'a str, item: impl Parser<'a, T>) -> impl Parser<'a, T> {
+    move |input: Span<'a>| item(input.trim().strip_prefix(prefix)?.trim())
+}
+
+/// A rectangular grid of characters, `cell` rejects a character with `None`
+pub fn grid<'a, T>(
+    expected: &'a str,
+    cell: impl Fn(char) -> Option<T>,
+) -> impl Parser<'a, Vec<Vec<T>>> {
+    move |input: Span<'a>| {
+        let mut rows: Vec<Vec<T>> = Vec::new();
+        for line in input.lines().map(|l| l.trim()) {
+            let row = line
+                .chars()
+                .map(|(span, c)| cell(c).ok_or_else(|| span.error(expected)))
+                .collect::<Result<Vec<T>, ParseError>>()?;
+            if let Some(first) = rows.first() {
+                if row.len() != first.len() {
+                    return Err(line.error(format!("{} cells per row", first.len())));
+                }
+            }
+            rows.push(row);
+        }
+        Ok(rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_combinators() {
+        let point = || pair(integer::<u32>, ",", integer::<u32>);
+        let segments = parse(
+            "0,9 -> 5,9\n8,0 -> 0,8\n",
+            lines(pair(point(), "->", point())),
+        );
+        assert_eq!(segments.unwrap(), vec![((0, 9), (5, 9)), ((8, 0), (0, 8))]);
+
+        let rules_and_updates = pair(
+            lines(pair(integer::<u32>, "|", integer::<u32>)),
+            "\n\n",
+            lines(separated(",", integer::<u32>)),
+        );
+        let (rules, updates) = parse("47|53\n97|13\n\n75,47,61", rules_and_updates).unwrap();
+        assert_eq!(rules, vec![(47, 53), (97, 13)]);
+        assert_eq!(updates, vec![vec![75, 47, 61]]);
+
+        let card = prefixed(
+            "Card",
+            pair(
+                integer::<u32>,
+                ":",
+                pair(words(integer::<u32>), "|", words(integer::<u32>)),
+            ),
+        );
+        let (id, (winning, yours)) = parse("Card  1: 41 48 | 83 86  6", card).unwrap();
+        assert_eq!((id, winning, yours), (1, vec![41, 48], vec![83, 86, 6]));
+
+        let fold = prefixed(
+            "fold along",
+            pair(map(text, |axis| axis == "x"), "=", integer::<u32>),
+        );
+        assert_eq!(parse("fold along y=7", fold), Ok((false, 7)));
+
+        let rules = key_values(text, "->", integer::<char>);
+        let rules = parse("CH -> B\nHH -> N", rules).unwrap();
+        assert_eq!((rules.len(), rules["HH"]), (2, 'N'));
+
+        let blocks = parse("1\n2\n\n3", sections(lines(integer::<u32>))).unwrap();
+        assert_eq!(blocks, vec![vec![1, 2], vec![3]]);
+
+        let heights = parse("219\n398", grid("a height", |c| c.to_digit(10))).unwrap();
+        assert_eq!(heights, vec![vec![2, 1, 9], vec![3, 9, 8]]);
+    }
+
+    #[test]
+    fn test_combinator_errors() {
+        let segment = lines(pair(
+            pair(integer::<u32>, ",", integer::<u32>),
+            "->",
+            pair(integer::<u32>, ",", integer::<u32>),
+        ));
+        let error = parse("0,9 -> 5,9\n8,0 0,8", segment).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 1: expected `->`, found \"8,0 0,8\""
+        );
+
+        let error = parse("1,2\n3,x", lines(separated(",", integer::<u8>))).unwrap_err();
+        assert_eq!(
+            (error.line, error.column, error.expected.as_str()),
+            (2, 3, "u8")
+        );
+
+        let error = parse("219\n3a8", grid("a height", |c| c.to_digit(10))).unwrap_err();
+        assert_eq!((error.line, error.column, error.text.as_str()), (2, 2, "a"));
+
+        let error = parse("219\n39", grid("a height", |c| c.to_digit(10))).unwrap_err();
+        assert_eq!(
+            (error.line, error.expected.as_str()),
+            (2, "3 cells per row")
+        );
+
+        let rules = key_values(text, "->", integer::<char>);
+        let error = parse("CH -> B\nHH -> N\n CH -> C", rules).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 3, column 2: expected a key that isn't defined yet, found \"CH\""
+        );
+
+        let error = parse("Game 1: 3 blue", prefixed("Card", integer::<u32>)).unwrap_err();
+        assert_eq!(error.expected, "`Card`");
+    }
+}
@@ -0,0 +1,315 @@
+//! A small constraint solver over finite domains, for puzzles that are about
+//! finding the one assignment that fits every clue (wirings, logic grids...)
+//!
+//! ```ignore
+//! let mut problem = Problem::new();
+//! let x = problem.variable(1..=3);
+//! let y = problem.variable(1..=3);
+//! problem.all_different([x, y]);
+//! problem.constraint([x, y], |v| v[0] + v[1] == 5);
+//! let solution = problem.solve().unwrap();
+//! assert_eq!(solution[x] * solution[y], 6);
+//! ```
+//!
+//! Constraints prune the domains as soon as all but one of their variables
+//! are known, the search then tries the values of the variable with the
+//! fewest left
+
+use std::ops::Index;
+
+/// Handle to a variable of a [`Problem`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Variable(usize);
+
+/// Tells whether values, in the order of the constrained variables, fit
+type Predicate<'a, T> = Box<dyn Fn(&[&T]) -> bool + 'a>;
+
+enum Constraint<'a, T> {
+    AllDifferent(Vec<Variable>),
+    Predicate {
+        variables: Vec<Variable>,
+        predicate: Predicate<'a, T>,
+    },
+}
+
+/// Variables with their possible values, and the constraints between them
+pub struct Problem<'a, T> {
+    domains: Vec<Vec<T>>,
+    constraints: Vec<Constraint<'a, T>>,
+}
+
+/// The indices of the values each variable may still take
+type Domains = Vec<Vec<usize>>;
+
+impl<T> Default for Problem<'_, T> {
+    fn default() -> Self {
+        Self {
+            domains: Vec::new(),
+            constraints: Vec::new(),
+        }
+    }
+}
+
+impl<'a, T: PartialEq> Problem<'a, T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A new variable taking one of `domain`
+    pub fn variable(&mut self, domain: impl IntoIterator<Item = T>) -> Variable {
+        self.domains.push(domain.into_iter().collect());
+        Variable(self.domains.len() - 1)
+    }
+
+    /// No two of `variables` take equal values
+    pub fn all_different(&mut self, variables: impl IntoIterator<Item = Variable>) {
+        self.constraints
+            .push(Constraint::AllDifferent(variables.into_iter().collect()));
+    }
+
+    /// `predicate` holds for the values of `variables`, given in that order
+    pub fn constraint(
+        &mut self,
+        variables: impl IntoIterator<Item = Variable>,
+        predicate: impl Fn(&[&T]) -> bool + 'a,
+    ) {
+        self.constraints.push(Constraint::Predicate {
+            variables: variables.into_iter().collect(),
+            predicate: Box::new(predicate),
+        });
+    }
+
+    /// The first assignment found that satisfies every constraint
+    pub fn solve(&self) -> Option<Solution<'_, T>> {
+        let mut solutions = Vec::new();
+        self.search(self.initial_domains(), &mut solutions, 1);
+        solutions.pop()
+    }
+
+    /// Every assignment that satisfies every constraint, there may be a lot
+    pub fn solutions(&self) -> Vec<Solution<'_, T>> {
+        let mut solutions = Vec::new();
+        self.search(self.initial_domains(), &mut solutions, usize::MAX);
+        solutions
+    }
+
+    fn initial_domains(&self) -> Domains {
+        self.domains
+            .iter()
+            .map(|d| (0..d.len()).collect())
+            .collect()
+    }
+
+    /// Depth first, stops once `limit` solutions are found
+    fn search<'s>(
+        &'s self,
+        mut domains: Domains,
+        solutions: &mut Vec<Solution<'s, T>>,
+        limit: usize,
+    ) {
+        if !self.propagate(&mut domains) {
+            return;
+        }
+        let unassigned = (0..domains.len())
+            .filter(|&v| domains[v].len() > 1)
+            .min_by_key(|&v| domains[v].len());
+        let Some(variable) = unassigned else {
+            solutions.push(Solution {
+                values: (0..domains.len())
+                    .map(|v| &self.domains[v][domains[v][0]])
+                    .collect(),
+            });
+            return;
+        };
+        for &value in &domains[variable] {
+            if solutions.len() >= limit {
+                return;
+            }
+            let mut next = domains.clone();
+            next[variable] = vec![value];
+            self.search(next, solutions, limit);
+        }
+    }
+
+    /// Removes the values that can't satisfy a constraint anymore until
+    /// nothing changes, `false` once a domain is empty
+    fn propagate(&self, domains: &mut Domains) -> bool {
+        // NOTE, A variable can start with an empty domain, the constraints
+        // may not notice, e.g. when there are none
+        if domains.iter().any(|d| d.is_empty()) {
+            return false;
+        }
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for constraint in &self.constraints {
+                match constraint {
+                    Constraint::AllDifferent(variables) => {
+                        for &Variable(v) in variables {
+                            let [value] = domains[v][..] else {
+                                continue;
+                            };
+                            for &Variable(other) in variables {
+                                let before = domains[other].len();
+                                if other != v {
+                                    let values = &self.domains[other];
+                                    domains[other].retain(|&i| values[i] != self.domains[v][value]);
+                                }
+                                changed |= domains[other].len() != before;
+                            }
+                        }
+                    }
+                    Constraint::Predicate {
+                        variables,
+                        predicate,
+                    } => {
+                        // NOTE, Only a single unknown variable is narrowed
+                        // down, even if it appears more than once, a fully
+                        // known one is checked
+                        let mut unknown = variables
+                            .iter()
+                            .filter(|&&Variable(v)| domains[v].len() != 1);
+                        let target = match unknown.next() {
+                            Some(&target) if unknown.all(|&other| other == target) => target,
+                            Some(_) => continue,
+                            None => match variables.first() {
+                                Some(&first) => first,
+                                None => continue,
+                            },
+                        };
+                        let before = domains[target.0].len();
+                        let candidates = std::mem::take(&mut domains[target.0]);
+                        domains[target.0] = candidates
+                            .into_iter()
+                            .filter(|&candidate| {
+                                let values = variables
+                                    .iter()
+                                    .map(|&Variable(v)| {
+                                        let index = if v == target.0 {
+                                            candidate
+                                        } else {
+                                            domains[v][0]
+                                        };
+                                        &self.domains[v][index]
+                                    })
+                                    .collect::<Vec<_>>();
+                                predicate(&values)
+                            })
+                            .collect();
+                        changed |= domains[target.0].len() != before;
+                    }
+                }
+                if domains.iter().any(|d| d.is_empty()) {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+}
+
+/// A value for every variable of a [`Problem`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution<'p, T> {
+    values: Vec<&'p T>,
+}
+
+impl<T> Index<Variable> for Solution<'_, T> {
+    type Output = T;
+
+    fn index(&self, Variable(v): Variable) -> &T {
+        self.values[v]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_all_different() {
+        let mut problem = Problem::new();
+        let x = problem.variable(1..=3);
+        let y = problem.variable(1..=3);
+        problem.all_different([x, y]);
+        problem.constraint([x, y], |v| v[0] + v[1] == 5);
+        let solution = problem.solve().unwrap();
+        assert_eq!(solution[x] * solution[y], 6);
+        assert_eq!(problem.solutions().len(), 2);
+
+        problem.constraint([x], |v| *v[0] == 1);
+        assert!(problem.solve().is_none());
+    }
+
+    #[test]
+    fn test_empty_domain() {
+        let mut problem = Problem::<u32>::new();
+        problem.variable(vec![]);
+        assert!(problem.solve().is_none());
+        assert!(problem.solutions().is_empty());
+
+        problem.variable(1..=3);
+        assert!(problem.solve().is_none());
+    }
+
+    #[test]
+    fn test_repeated_variable_is_narrowed() {
+        let mut problem = Problem::new();
+        let x = problem.variable(0..=9);
+        let y = problem.variable(0..=9);
+        problem.constraint([y], |v| *v[0] == 3);
+        problem.constraint([x, y, x], |v| v[0] + v[1] + v[2] == 9);
+
+        let mut domains = problem.initial_domains();
+        assert!(problem.propagate(&mut domains));
+        assert_eq!(domains[x.0], vec![3]);
+        assert_eq!(domains[y.0], vec![3]);
+    }
+
+    #[test]
+    fn test_send_more_money() {
+        // NOTE, SEND + MORE = MONEY with a distinct digit per letter, added
+        // column by column with the carries as variables too
+        let mut problem = Problem::new();
+        let [s, e, n, d, m, o, r, y] = [(); 8].map(|_| problem.variable(0..=9));
+        let [c1, c2, c3] = [(); 3].map(|_| problem.variable(0..=1));
+        problem.all_different([s, e, n, d, m, o, r, y]);
+        problem.constraint([s], |v| *v[0] != 0);
+        problem.constraint([m], |v| *v[0] == 1);
+        problem.constraint([d, e, y, c1], |v| v[0] + v[1] == v[2] + 10 * v[3]);
+        problem.constraint([n, r, c1, e, c2], |v| {
+            v[0] + v[1] + v[2] == v[3] + 10 * v[4]
+        });
+        problem.constraint([e, o, c2, n, c3], |v| {
+            v[0] + v[1] + v[2] == v[3] + 10 * v[4]
+        });
+        problem.constraint([s, m, c3, o, m], |v| v[0] + v[1] + v[2] == v[3] + 10 * v[4]);
+
+        let solutions = problem.solutions();
+        assert_eq!(solutions.len(), 1);
+        let solution = &solutions[0];
+        let value = |w: &[Variable]| w.iter().fold(0, |n, &v| n * 10 + solution[v]);
+        assert_eq!(value(&[s, e, n, d]), 9567);
+        assert_eq!(value(&[m, o, r, e]), 1085);
+        assert_eq!(value(&[m, o, n, e, y]), 10652);
+    }
+
+    #[test]
+    fn test_logic_grid() {
+        // NOTE, Three people each own a different pet, Alice doesn't own
+        // the cat, the dog owner is Bob and Carol doesn't own the fish
+        let pets = ["cat", "dog", "fish"];
+        let mut problem = Problem::new();
+        let [alice, bob, carol] = [(); 3].map(|_| problem.variable(pets));
+        problem.all_different([alice, bob, carol]);
+        problem.constraint([alice], |v| *v[0] != "cat");
+        problem.constraint([bob], |v| *v[0] == "dog");
+        problem.constraint([carol], |v| *v[0] != "fish");
+
+        let solution = problem.solve().unwrap();
+        assert_eq!(
+            [solution[alice], solution[bob], solution[carol]],
+            ["fish", "dog", "cat"]
+        );
+    }
+}
@@ -0,0 +1,338 @@
+//! Checks that brackets and other delimiters are balanced, and says how to
+//! close the ones left open
+//!
+//! ```text
+//! {([(<{}[<>[]}>{[]{[(<()>   `}` instead of `]` at column 13
+//! [({(<(())[]>[[{[]{<()<>>   incomplete, `}}]])})]` closes it
+//! ```
+//!
+//! Quoted text is skipped, so `f(")")` is balanced, and an escape character
+//! makes the next character plain text wherever it is
+
+use std::collections::HashMap;
+
+/// Where a character is in the checked text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    /// Offset in bytes
+    pub offset: usize,
+    /// 1-based line
+    pub line: usize,
+    /// 1-based column, in characters
+    pub column: usize,
+}
+
+/// What [`DelimiterChecker::check`] found
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Diagnostic {
+    /// Every delimiter is closed
+    Balanced,
+    /// `found` closes something that isn't the innermost open delimiter,
+    /// `expected` is the closer that would have fit, `None` when nothing was
+    /// open
+    Mismatch {
+        position: Position,
+        found: char,
+        expected: Option<char>,
+        score: u64,
+    },
+    /// Delimiters are left open, or a quote, `completion` closes them from
+    /// the innermost one out
+    Incomplete {
+        /// Where each open delimiter was opened, outermost first
+        open: Vec<Position>,
+        completion: String,
+        score: u64,
+    },
+}
+
+/// Scores the problems found by a [`DelimiterChecker`], both are 0 by default
+pub trait Scoring {
+    fn mismatch(&self, _found: char) -> u64 {
+        0
+    }
+
+    fn completion(&self, _completion: &str) -> u64 {
+        0
+    }
+}
+
+/// Nothing is scored
+impl Scoring for () {}
+
+/// A fixed score per wrong closer, and a completion score where each closer
+/// multiplies the score so far by `base` before adding its value
+///
+/// Closers missing from a table are worth 0, scores saturate at `u64::MAX`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TableScoring {
+    pub mismatch: HashMap<char, u64>,
+    pub completion: HashMap<char, u64>,
+    pub base: u64,
+}
+
+impl Scoring for TableScoring {
+    fn mismatch(&self, found: char) -> u64 {
+        self.mismatch.get(&found).copied().unwrap_or(0)
+    }
+
+    fn completion(&self, completion: &str) -> u64 {
+        completion.chars().fold(0, |score, c| {
+            let value = self.completion.get(&c).copied().unwrap_or(0);
+            score.saturating_mul(self.base).saturating_add(value)
+        })
+    }
+}
+
+/// What a character means to the checker
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Role {
+    Open(char),
+    Close,
+    Quote(char),
+    Escape,
+}
+
+/// Checks delimiters configured as open/close pairs, quotes and an escape
+/// character
+///
+/// ```ignore
+/// let checker = DelimiterChecker::new()
+///     .pair('(', ')')
+///     .pair('[', ']')
+///     .quote('"', '"')
+///     .escape('\\');
+/// ```
+///
+/// Every character may only have one role, configuring it twice panics
+#[derive(Debug, Clone, Default)]
+pub struct DelimiterChecker<S = ()> {
+    roles: HashMap<char, Role>,
+    scoring: S,
+}
+
+impl DelimiterChecker {
+    /// A checker without any delimiters, everything is balanced
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<S: Scoring> DelimiterChecker<S> {
+    fn with_role(mut self, c: char, role: Role) -> Self {
+        let previous = self.roles.insert(c, role);
+        assert!(previous.is_none(), "{:?} is configured twice", c);
+        self
+    }
+
+    /// `open` must be closed by `close`, the two must differ
+    pub fn pair(self, open: char, close: char) -> Self {
+        assert_ne!(open, close, "a pair needs different open and close chars");
+        self.with_role(open, Role::Open(close))
+            .with_role(close, Role::Close)
+    }
+
+    /// Everything from `open` up to `close` is plain text, the two may be
+    /// the same character
+    pub fn quote(self, open: char, close: char) -> Self {
+        let checker = self.with_role(open, Role::Quote(close));
+        if open == close {
+            checker
+        } else {
+            checker.with_role(close, Role::Close)
+        }
+    }
+
+    /// The character after `escape` is plain text, in quotes or not
+    pub fn escape(self, escape: char) -> Self {
+        self.with_role(escape, Role::Escape)
+    }
+
+    pub fn with_scoring<T: Scoring>(self, scoring: T) -> DelimiterChecker<T> {
+        DelimiterChecker {
+            roles: self.roles,
+            scoring,
+        }
+    }
+
+    /// Stops at the first mismatch
+    pub fn check(&self, text: &str) -> Diagnostic {
+        // NOTE, The closer expected for every open delimiter, innermost last
+        let mut open: Vec<(Position, char)> = Vec::new();
+        let mut quote: Option<(Position, char)> = None;
+        let mut escaped = false;
+        let mut position = Position {
+            offset: 0,
+            line: 1,
+            column: 1,
+        };
+
+        for (offset, c) in text.char_indices() {
+            position.offset = offset;
+            let role = self.roles.get(&c).copied();
+            match (escaped, quote, role) {
+                (true, _, _) => escaped = false,
+                (false, _, Some(Role::Escape)) => escaped = true,
+                (false, Some((_, close)), _) => {
+                    if c == close {
+                        quote = None;
+                    }
+                }
+                (false, None, Some(Role::Quote(close))) => quote = Some((position, close)),
+                (false, None, Some(Role::Open(close))) => open.push((position, close)),
+                (false, None, Some(Role::Close)) => match open.pop() {
+                    Some((_, expected)) if expected == c => {}
+                    expected => {
+                        return Diagnostic::Mismatch {
+                            position,
+                            found: c,
+                            expected: expected.map(|(_, expected)| expected),
+                            score: self.scoring.mismatch(c),
+                        }
+                    }
+                },
+                (false, None, None) => {}
+            }
+
+            if c == '\n' {
+                position.line += 1;
+                position.column = 1;
+            } else {
+                position.column += 1;
+            }
+        }
+
+        let completion = quote
+            .iter()
+            .chain(open.iter().rev())
+            .map(|&(_, close)| close)
+            .collect::<String>();
+        if completion.is_empty() {
+            return Diagnostic::Balanced;
+        }
+        Diagnostic::Incomplete {
+            open: open.iter().chain(&quote).map(|&(p, _)| p).collect(),
+            score: self.scoring.completion(&completion),
+            completion,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn brackets() -> DelimiterChecker {
+        DelimiterChecker::new()
+            .pair('(', ')')
+            .pair('[', ']')
+            .pair('{', '}')
+            .pair('<', '>')
+    }
+
+    #[test]
+    fn test_mismatch() {
+        let diagnostic = brackets().check("{([(<{}[<>[]}>{[]{[(<()>");
+        assert_eq!(
+            diagnostic,
+            Diagnostic::Mismatch {
+                position: Position {
+                    offset: 12,
+                    line: 1,
+                    column: 13
+                },
+                found: '}',
+                expected: Some(']'),
+                score: 0,
+            }
+        );
+
+        let diagnostic = brackets().check("()\n  )(");
+        assert!(matches!(
+            diagnostic,
+            Diagnostic::Mismatch {
+                position: Position {
+                    line: 2,
+                    column: 3,
+                    ..
+                },
+                found: ')',
+                expected: None,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_completion() {
+        assert_eq!(brackets().check("<{}>[]"), Diagnostic::Balanced);
+        assert_eq!(brackets().check(""), Diagnostic::Balanced);
+
+        let scoring = TableScoring {
+            mismatch: HashMap::new(),
+            completion: HashMap::from([(')', 1), (']', 2), ('}', 3), ('>', 4)]),
+            base: 5,
+        };
+        let Diagnostic::Incomplete {
+            open,
+            completion,
+            score,
+        } = brackets()
+            .with_scoring(scoring)
+            .check("[({(<(())[]>[[{[]{<()<>>")
+        else {
+            panic!("the line is incomplete");
+        };
+        assert_eq!(completion, "}}]])})]");
+        assert_eq!(score, 288957);
+        assert_eq!(open.len(), 8);
+        assert_eq!(open[0].column, 1);
+    }
+
+    #[test]
+    fn test_quotes_and_escapes() {
+        let checker = brackets().quote('"', '"').quote('«', '»').escape('\\');
+        assert_eq!(
+            checker.check(r#"f(")", ']')"#),
+            Diagnostic::Mismatch {
+                position: Position {
+                    offset: 8,
+                    line: 1,
+                    column: 9
+                },
+                found: ']',
+                expected: Some(')'),
+                score: 0,
+            }
+        );
+        assert_eq!(checker.check(r#"f(")", \])"#), Diagnostic::Balanced);
+        assert_eq!(checker.check(r#"f("\")")"#), Diagnostic::Balanced);
+        assert_eq!(checker.check("«(» [«]»]"), Diagnostic::Balanced);
+
+        let diagnostic = checker.check(r#"f(["\"a"#);
+        let Diagnostic::Incomplete {
+            open, completion, ..
+        } = diagnostic
+        else {
+            panic!("the quote is left open");
+        };
+        assert_eq!(completion, "\"])");
+        assert_eq!(open.iter().map(|p| p.offset).collect::<Vec<_>>(), [1, 2, 3]);
+
+        // NOTE, An unmatched closing quote is a closer like any other
+        assert!(matches!(
+            checker.check("(»)"),
+            Diagnostic::Mismatch {
+                found: '»',
+                expected: Some(')'),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_roles_are_exclusive() {
+        brackets().quote('(', ')');
+    }
+}
@@ -0,0 +1,36 @@
+use std::{cell::RefCell, fmt::Display};
+
+thread_local! {
+    static NOTES: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Leaves a note about the input for the runner to show with `--notes`
+///
+/// Solutions only return their answer, notes explain it, e.g. which rule an
+/// update breaks
+///
+/// ```ignore
+/// diagnostics::note(format!("update {} breaks {}", index, violation));
+/// ```
+pub fn note(message: impl Display) {
+    NOTES.with_borrow_mut(|notes| notes.push(message.to_string()));
+}
+
+/// Notes left on the current thread since the last call
+pub fn take_notes() -> Vec<String> {
+    NOTES.take()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notes_are_taken_once() {
+        take_notes();
+        note("first");
+        note(2);
+        assert_eq!(take_notes(), ["first", "2"]);
+        assert!(take_notes().is_empty());
+    }
+}
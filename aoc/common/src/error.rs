@@ -0,0 +1,59 @@
+use std::{error::Error, fmt, io, path::PathBuf};
+
+use crate::{ocr::OcrError, ParseError};
+
+/// The answer printed for a puzzle part
+pub type Answer = String;
+
+/// Everything that can go wrong between locating a puzzle input and answering it
+#[derive(Debug)]
+pub enum AocError {
+    /// The input file does not exist
+    MissingInput(PathBuf),
+    /// The input file exists but could not be read
+    Io { path: PathBuf, source: io::Error },
+    /// The input does not have the format the solution expects
+    Parse(ParseError),
+    /// The answer is drawn in letters that couldn't be read
+    Ocr(OcrError),
+    /// The input is well formed but has no answer, e.g. rules contradict
+    /// each other
+    Unsolvable(String),
+}
+
+impl fmt::Display for AocError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AocError::MissingInput(path) => write!(f, "missing input {}", path.display()),
+            AocError::Io { path, source } => {
+                write!(f, "couldn't read {}: {}", path.display(), source)
+            }
+            AocError::Parse(e) => write!(f, "invalid input at {}", e),
+            AocError::Ocr(e) => write!(f, "couldn't read the answer, {}", e),
+            AocError::Unsolvable(reason) => write!(f, "no answer, {}", reason),
+        }
+    }
+}
+
+impl Error for AocError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            AocError::Io { source, .. } => Some(source),
+            AocError::Parse(e) => Some(e),
+            AocError::Ocr(e) => Some(e),
+            AocError::MissingInput(_) | AocError::Unsolvable(_) => None,
+        }
+    }
+}
+
+impl From<ParseError> for AocError {
+    fn from(e: ParseError) -> Self {
+        AocError::Parse(e)
+    }
+}
+
+impl From<OcrError> for AocError {
+    fn from(e: OcrError) -> Self {
+        AocError::Ocr(e)
+    }
+}
@@ -0,0 +1,239 @@
+//! Tests generated from the examples stored in `<year>/examples`
+//!
+//! Every `dayN_example[_K].txt` needs an entry in `<year>/examples/answers.toml`
+//! and becomes one test per recorded part, named after the file
+//!
+//! ```toml
+//! # dayN_example.txt
+//! [day12]
+//! part1 = "10"
+//! part2 = "36"
+//!
+//! # dayN_example_2.txt
+//! [day12_2]
+//! part1 = "19"
+//! ```
+//!
+//! A year's build script calls [`generate_tests`] and its `lib.rs` includes
+//! the result
+//!
+//! ```ignore
+//! #[cfg(test)]
+//! mod examples {
+//!     include!(concat!(env!("OUT_DIR"), "/examples.rs"));
+//! }
+//! ```
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+use crate::{Answer, Registry};
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ExampleAnswers {
+    part1: Option<Answer>,
+    part2: Option<Answer>,
+}
+
+/// One recorded answer for an example file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fixture {
+    pub day: u32,
+    /// The K of `dayN_example_K.txt`, 1 for `dayN_example.txt`
+    pub example: u32,
+    pub part: u32,
+    pub answer: Answer,
+}
+
+impl Fixture {
+    /// `dayN_example.txt` or `dayN_example_K.txt`, like the runner's `--example`
+    pub fn filename(&self) -> String {
+        match self.example {
+            1 => format!("day{}_example.txt", self.day),
+            k => format!("day{}_example_{}.txt", self.day, k),
+        }
+    }
+
+    /// Name of the generated test, e.g. `day12_example_2_part1`
+    pub fn test_name(&self) -> String {
+        match self.example {
+            1 => format!("day{}_example_part{}", self.day, self.part),
+            k => format!("day{}_example_{}_part{}", self.day, k, self.part),
+        }
+    }
+}
+
+/// Parses `[dayN]` and `[dayN_K]` sections with optional `part1` and `part2`
+/// answers, ordered by day, example and part
+pub fn parse_answers(content: &str) -> Result<Vec<Fixture>, String> {
+    let sections: BTreeMap<String, ExampleAnswers> =
+        toml::from_str(content).map_err(|e| e.to_string())?;
+
+    let mut fixtures = Vec::new();
+    for (section, answers) in sections {
+        let invalid = || format!("Invalid section [{}], expected [dayN] or [dayN_K]", section);
+        let name = section.strip_prefix("day").ok_or_else(invalid)?;
+        let (day, example) = match name.split_once('_') {
+            Some((day, example)) => (day.parse(), example.parse()),
+            None => (name.parse(), Ok(1)),
+        };
+        let (Ok(day), Ok(example @ 1..)) = (day, example) else {
+            return Err(invalid());
+        };
+        for (part, answer) in [(1, answers.part1), (2, answers.part2)] {
+            if let Some(answer) = answer {
+                fixtures.push(Fixture {
+                    day,
+                    example,
+                    part,
+                    answer,
+                });
+            }
+        }
+    }
+    fixtures.sort_by_key(|f| (f.day, f.example, f.part));
+    Ok(fixtures)
+}
+
+/// Whether `name` is `dayN_example.txt` or `dayN_example_K.txt`
+fn is_example(name: &str) -> bool {
+    let Some((day, example)) = name
+        .strip_prefix("day")
+        .and_then(|name| name.strip_suffix(".txt"))
+        .and_then(|name| name.split_once("_example"))
+    else {
+        return false;
+    };
+    let numeric = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    numeric(day) && (example.is_empty() || example.strip_prefix('_').is_some_and(numeric))
+}
+
+/// Writes `$OUT_DIR/examples.rs` with one test per answer recorded in
+/// `examples/answers.toml`, meant to be called from a year's `build.rs`
+///
+/// Panics, failing the build, when the answers can't be read, an answer is
+/// recorded for a missing example or an example has no recorded answer
+pub fn generate_tests(year: u32) {
+    let examples = Path::new(&env::var_os("CARGO_MANIFEST_DIR").unwrap()).join("examples");
+    let out = PathBuf::from(env::var_os("OUT_DIR").unwrap()).join("examples.rs");
+    // NOTE, Watching the directory also catches examples being added
+    println!("cargo:rerun-if-changed={}", examples.display());
+
+    let path = examples.join("answers.toml");
+    let fixtures = match fs::read_to_string(&path) {
+        Ok(content) => {
+            parse_answers(&content).unwrap_or_else(|e| panic!("{}: {}", path.display(), e))
+        }
+        Err(_) => Vec::new(),
+    };
+
+    let mut tests = String::new();
+    for fixture in &fixtures {
+        let input = examples.join(fixture.filename());
+        assert!(
+            input.exists(),
+            "{}: an answer is recorded for the missing {}",
+            path.display(),
+            input.display()
+        );
+        tests += &format!(
+            "#[test]\nfn {}() {{\n    ::common::examples::check({}, {}, {}, include_str!({:?}), {:?});\n}}\n\n",
+            fixture.test_name(),
+            year,
+            fixture.day,
+            fixture.part,
+            input,
+            fixture.answer
+        );
+    }
+    // NOTE, An example without answers would silently never be tested
+    let answered = fixtures
+        .iter()
+        .map(Fixture::filename)
+        .collect::<BTreeSet<_>>();
+    for entry in fs::read_dir(&examples).into_iter().flatten().flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if is_example(&name) {
+            assert!(
+                answered.contains(&name),
+                "{}: no answer is recorded for {}",
+                path.display(),
+                entry.path().display()
+            );
+        }
+    }
+    fs::write(&out, tests).unwrap_or_else(|e| panic!("{}: {}", out.display(), e));
+}
+
+/// Asserts that every implementation of a part answers `expected` for `input`
+pub fn check(year: u32, day: u32, part: u32, input: &str, expected: &str) {
+    let registry = Registry::collect();
+    let implementations = registry.implementations(year, day, part);
+    assert!(
+        !implementations.is_empty(),
+        "Year {} Day {} Part {} has no solution",
+        year,
+        day,
+        part
+    );
+    for solution in implementations {
+        let variant = solution.variant().unwrap_or("default");
+        match solution.solve(input) {
+            Ok(answer) => assert_eq!(answer, expected, "`{}` answers wrong", variant),
+            Err(e) => panic!("`{}` fails, {}", variant, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_answers() {
+        let fixtures = parse_answers(
+            "[day12_2]\npart1 = \"19\"\n\n[day12]\npart1 = \"10\"\npart2 = \"36\"\n\n[day3]\n",
+        )
+        .unwrap();
+        let names = fixtures
+            .iter()
+            .map(|f| (f.filename(), f.test_name(), f.answer.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [
+                ("day12_example.txt", "day12_example_part1", "10"),
+                ("day12_example.txt", "day12_example_part2", "36"),
+                ("day12_example_2.txt", "day12_example_2_part1", "19"),
+            ]
+            .map(|(f, t, a)| (f.to_string(), t.to_string(), a))
+        );
+
+        for section in ["[dayX]", "[day1_0]", "[example1]", "[day1_2_3]"] {
+            assert!(parse_answers(section).is_err(), "{}", section);
+        }
+        assert!(parse_answers("[day1]\npart3 = \"1\"").is_err());
+    }
+
+    #[test]
+    fn test_is_example() {
+        for name in ["day1_example.txt", "day12_example_2.txt"] {
+            assert!(is_example(name), "{}", name);
+        }
+        for name in [
+            "answers.toml",
+            "day1.txt",
+            "dayX_example.txt",
+            "day1_example_.txt",
+            "day1_example2.txt",
+        ] {
+            assert!(!is_example(name), "{}", name);
+        }
+    }
+}
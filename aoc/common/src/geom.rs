@@ -0,0 +1,323 @@
+//! 2D points, directions and lines
+//!
+//! [`Point`] is for positions on a grid, which can't be negative. [`IPoint`]
+//! is for everything else, like a line going in any direction
+
+use std::ops::{Add, Sub};
+
+/// A position on a grid, `x` is the column and `y` the row, `(0, 0)` is the
+/// top left corner
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl Point {
+    pub const fn new(x: usize, y: usize) -> Self {
+        Self { x, y }
+    }
+
+    /// The point `(dx, dy)` away, `None` when it would leave the first quadrant
+    pub fn offset(&self, (dx, dy): (isize, isize)) -> Option<Point> {
+        Some(Point::new(
+            self.x.checked_add_signed(dx)?,
+            self.y.checked_add_signed(dy)?,
+        ))
+    }
+
+    /// The point `(dx, dy)` away, `None` when it isn't inside a `width` by
+    /// `height` area
+    pub fn step_within(&self, delta: (isize, isize), width: usize, height: usize) -> Option<Point> {
+        self.offset(delta).filter(|p| p.x < width && p.y < height)
+    }
+
+    pub fn manhattan(&self, other: Point) -> usize {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    pub fn chebyshev(&self, other: Point) -> usize {
+        self.x.abs_diff(other.x).max(self.y.abs_diff(other.y))
+    }
+
+    /// Every point from `self` to `to`, both included
+    ///
+    /// See [`IPoint::line_to`]
+    pub fn line_to(&self, to: Point) -> impl Iterator<Item = Point> {
+        IPoint::from(*self)
+            .line_to(IPoint::from(to))
+            .map(|p| Point::try_from(p).expect("A line between two points stays positive"))
+    }
+}
+
+impl From<(usize, usize)> for Point {
+    fn from((x, y): (usize, usize)) -> Self {
+        Point::new(x, y)
+    }
+}
+
+/// Fails when either coordinate is negative
+impl TryFrom<IPoint> for Point {
+    type Error = std::num::TryFromIntError;
+
+    fn try_from(point: IPoint) -> Result<Self, Self::Error> {
+        Ok(Point::new(point.x.try_into()?, point.y.try_into()?))
+    }
+}
+
+/// A point anywhere on the plane, `y` grows downwards like it does for
+/// [`Point`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct IPoint {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl IPoint {
+    pub const fn new(x: i64, y: i64) -> Self {
+        Self { x, y }
+    }
+
+    pub fn manhattan(&self, other: IPoint) -> u64 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    pub fn chebyshev(&self, other: IPoint) -> u64 {
+        self.x.abs_diff(other.x).max(self.y.abs_diff(other.y))
+    }
+
+    /// Every point from `self` to `to`, both included (Bresenham's algorithm)
+    ///
+    /// Horizontal, vertical and 45° lines hit exactly the points in between,
+    /// other slopes get the closest points to the real line
+    pub fn line_to(&self, to: IPoint) -> impl Iterator<Item = IPoint> {
+        let (dx, dy) = ((to.x - self.x).abs(), -(to.y - self.y).abs());
+        let (sx, sy) = ((to.x - self.x).signum(), (to.y - self.y).signum());
+        let mut error = dx + dy;
+        let mut current = Some(*self);
+        std::iter::from_fn(move || {
+            let point = current?;
+            current = (point != to).then(|| {
+                let mut next = point;
+                let doubled = 2 * error;
+                if doubled >= dy {
+                    error += dy;
+                    next.x += sx;
+                }
+                if doubled <= dx {
+                    error += dx;
+                    next.y += sy;
+                }
+                next
+            });
+            Some(point)
+        })
+    }
+}
+
+impl From<Point> for IPoint {
+    fn from(point: Point) -> Self {
+        IPoint::new(point.x as i64, point.y as i64)
+    }
+}
+
+impl From<(i64, i64)> for IPoint {
+    fn from((x, y): (i64, i64)) -> Self {
+        IPoint::new(x, y)
+    }
+}
+
+impl Add for IPoint {
+    type Output = IPoint;
+
+    fn add(self, other: IPoint) -> IPoint {
+        IPoint::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for IPoint {
+    type Output = IPoint;
+
+    fn sub(self, other: IPoint) -> IPoint {
+        IPoint::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+/// Up, right, down or left, with up being towards row 0
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction4 {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction4 {
+    /// Clockwise, starting from up
+    pub const ALL: [Direction4; 4] = [
+        Direction4::Up,
+        Direction4::Right,
+        Direction4::Down,
+        Direction4::Left,
+    ];
+
+    /// `(dx, dy)` of one step in this direction
+    pub fn delta(&self) -> (isize, isize) {
+        match self {
+            Direction4::Up => (0, -1),
+            Direction4::Right => (1, 0),
+            Direction4::Down => (0, 1),
+            Direction4::Left => (-1, 0),
+        }
+    }
+
+    /// A quarter turn clockwise
+    pub fn turn_right(&self) -> Self {
+        Self::ALL[(*self as usize + 1) % 4]
+    }
+
+    /// A quarter turn counter clockwise
+    pub fn turn_left(&self) -> Self {
+        Self::ALL[(*self as usize + 3) % 4]
+    }
+
+    pub fn opposite(&self) -> Self {
+        Self::ALL[(*self as usize + 2) % 4]
+    }
+}
+
+/// The 4 directions of [`Direction4`] and the 4 diagonals between them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction8 {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction8 {
+    /// Clockwise, starting from north (up)
+    pub const ALL: [Direction8; 8] = [
+        Direction8::North,
+        Direction8::NorthEast,
+        Direction8::East,
+        Direction8::SouthEast,
+        Direction8::South,
+        Direction8::SouthWest,
+        Direction8::West,
+        Direction8::NorthWest,
+    ];
+
+    /// `(dx, dy)` of one step in this direction
+    pub fn delta(&self) -> (isize, isize) {
+        match self {
+            Direction8::North => (0, -1),
+            Direction8::NorthEast => (1, -1),
+            Direction8::East => (1, 0),
+            Direction8::SouthEast => (1, 1),
+            Direction8::South => (0, 1),
+            Direction8::SouthWest => (-1, 1),
+            Direction8::West => (-1, 0),
+            Direction8::NorthWest => (-1, -1),
+        }
+    }
+
+    /// An eighth of a turn clockwise, e.g. north to north east
+    pub fn turn_right(&self) -> Self {
+        Self::ALL[(*self as usize + 1) % 8]
+    }
+
+    /// An eighth of a turn counter clockwise, e.g. north to north west
+    pub fn turn_left(&self) -> Self {
+        Self::ALL[(*self as usize + 7) % 8]
+    }
+
+    pub fn opposite(&self) -> Self {
+        Self::ALL[(*self as usize + 4) % 8]
+    }
+}
+
+impl From<Direction4> for Direction8 {
+    fn from(direction: Direction4) -> Self {
+        Direction8::ALL[direction as usize * 2]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_point_offset() {
+        let p = Point::new(1, 0);
+        assert_eq!(p.offset((1, 2)), Some(Point::new(2, 2)));
+        assert_eq!(p.offset((-1, 0)), Some(Point::new(0, 0)));
+        assert_eq!(p.offset((0, -1)), None);
+        assert_eq!(Point::from((3, 4)), Point::new(3, 4));
+
+        assert_eq!(p.step_within((1, 0), 3, 1), Some(Point::new(2, 0)));
+        assert_eq!(p.step_within((0, 1), 3, 1), None);
+        assert_eq!(
+            p.step_within(Direction4::Left.delta(), 3, 1),
+            Some(Point::new(0, 0))
+        );
+
+        assert_eq!(Point::try_from(IPoint::new(2, 5)), Ok(Point::new(2, 5)));
+        assert!(Point::try_from(IPoint::new(2, -5)).is_err());
+    }
+
+    #[test]
+    fn test_distances() {
+        let (a, b) = (Point::new(1, 8), Point::new(4, 2));
+        assert_eq!((a.manhattan(b), a.chebyshev(b)), (9, 6));
+        let (a, b) = (IPoint::new(-1, 8), IPoint::new(4, -2));
+        assert_eq!((a.manhattan(b), a.chebyshev(b)), (15, 10));
+        assert_eq!(a + b - b, a);
+    }
+
+    #[test]
+    fn test_directions() {
+        assert_eq!(Direction4::Up.turn_right(), Direction4::Right);
+        assert_eq!(Direction4::Up.turn_left(), Direction4::Left);
+        assert_eq!(Direction4::Left.turn_right(), Direction4::Up);
+        assert_eq!(Direction4::Right.opposite(), Direction4::Left);
+
+        assert_eq!(Direction8::NorthWest.turn_right(), Direction8::North);
+        assert_eq!(Direction8::North.turn_left(), Direction8::NorthWest);
+        assert_eq!(Direction8::SouthEast.opposite(), Direction8::NorthWest);
+        assert_eq!(Direction8::from(Direction4::Left), Direction8::West);
+
+        for direction in Direction8::ALL {
+            let (dx, dy) = direction.delta();
+            let (ox, oy) = direction.opposite().delta();
+            assert_eq!((dx + ox, dy + oy), (0, 0));
+        }
+    }
+
+    #[test]
+    fn test_line_to() {
+        let line = |from: (i64, i64), to: (i64, i64)| {
+            IPoint::from(from)
+                .line_to(to.into())
+                .map(|p| (p.x, p.y))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(line((0, 9), (3, 9)), vec![(0, 9), (1, 9), (2, 9), (3, 9)]);
+        assert_eq!(line((2, 2), (2, 0)), vec![(2, 2), (2, 1), (2, 0)]);
+        assert_eq!(line((3, 0), (0, 3)), vec![(3, 0), (2, 1), (1, 2), (0, 3)]);
+        assert_eq!(
+            line((0, 0), (4, 2)),
+            vec![(0, 0), (1, 1), (2, 1), (3, 2), (4, 2)]
+        );
+        assert_eq!(line((-1, -1), (-1, -1)), vec![(-1, -1)]);
+
+        let points = Point::new(8, 0)
+            .line_to(Point::new(6, 2))
+            .collect::<Vec<_>>();
+        assert_eq!(points, [(8, 0), (7, 1), (6, 2)].map(Point::from));
+    }
+}
@@ -0,0 +1,390 @@
+//! A directed, weighted adjacency list graph and the usual search algorithms
+//!
+//! Nodes are any hashable id (cave names, page numbers, grid points, ...). They
+//! are stored in insertion order so every traversal is deterministic
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, VecDeque},
+    error::Error,
+    fmt,
+    hash::Hash,
+};
+
+#[derive(Debug, Clone)]
+pub struct Graph<N> {
+    index: HashMap<N, usize>,
+    nodes: Vec<N>,
+    edges: Vec<Vec<(usize, u64)>>,
+}
+
+impl<N> Default for Graph<N> {
+    fn default() -> Self {
+        Self {
+            index: HashMap::new(),
+            nodes: Vec::new(),
+            edges: Vec::new(),
+        }
+    }
+}
+
+/// The graph has at least one cycle, so it can't be ordered
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleError<N> {
+    /// Nodes that are on a cycle or can only be reached through one
+    pub nodes: Vec<N>,
+}
+
+impl<N: fmt::Debug> fmt::Display for CycleError<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cycle between {:?}", self.nodes)
+    }
+}
+
+impl<N: fmt::Debug> Error for CycleError<N> {}
+
+impl<N: Clone + Eq + Hash> Graph<N> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `node` if it isn't in the graph yet
+    pub fn add_node(&mut self, node: N) {
+        self.id(node);
+    }
+
+    /// Edge from `from` to `to` with a weight of 1
+    pub fn add_edge(&mut self, from: N, to: N) {
+        self.add_weighted_edge(from, to, 1);
+    }
+
+    pub fn add_weighted_edge(&mut self, from: N, to: N, weight: u64) {
+        let (from, to) = (self.id(from), self.id(to));
+        self.edges[from].push((to, weight));
+    }
+
+    /// Edges both ways between `a` and `b`
+    pub fn add_undirected_edge(&mut self, a: N, b: N) {
+        self.add_edge(a.clone(), b.clone());
+        self.add_edge(b, a);
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn contains(&self, node: &N) -> bool {
+        self.index.contains_key(node)
+    }
+
+    /// Every node, in the order they were added
+    pub fn nodes(&self) -> impl Iterator<Item = &N> {
+        self.nodes.iter()
+    }
+
+    /// Nodes reachable with one edge from `node`
+    pub fn neighbours(&self, node: &N) -> impl Iterator<Item = &N> {
+        self.weighted_neighbours(node).map(|(n, _)| n)
+    }
+
+    pub fn weighted_neighbours(&self, node: &N) -> impl Iterator<Item = (&N, u64)> {
+        let edges = match self.index.get(node) {
+            Some(&id) => self.edges[id].as_slice(),
+            None => &[],
+        };
+        edges.iter().map(|&(to, weight)| (&self.nodes[to], weight))
+    }
+
+    /// Nodes reachable from `start` in breadth first order, along with the
+    /// number of edges to get there
+    pub fn bfs(&self, start: &N) -> Vec<(N, usize)> {
+        let Some(&start) = self.index.get(start) else {
+            return Vec::new();
+        };
+        let mut seen = vec![false; self.len()];
+        seen[start] = true;
+        let mut queue = VecDeque::from([(start, 0)]);
+        let mut order = Vec::new();
+        while let Some((node, depth)) = queue.pop_front() {
+            order.push((self.nodes[node].clone(), depth));
+            for &(next, _) in &self.edges[node] {
+                if !seen[next] {
+                    seen[next] = true;
+                    queue.push_back((next, depth + 1));
+                }
+            }
+        }
+        order
+    }
+
+    /// Nodes reachable from `start` in depth first (pre) order
+    pub fn dfs(&self, start: &N) -> Vec<N> {
+        let Some(&start) = self.index.get(start) else {
+            return Vec::new();
+        };
+        let mut seen = vec![false; self.len()];
+        let mut stack = vec![start];
+        let mut order = Vec::new();
+        while let Some(node) = stack.pop() {
+            if std::mem::replace(&mut seen[node], true) {
+                continue;
+            }
+            order.push(self.nodes[node].clone());
+            // NOTE, Pushed in reverse so the first edge is explored first
+            stack.extend(self.edges[node].iter().rev().map(|&(next, _)| next));
+        }
+        order
+    }
+
+    /// Every path from `start` to `end`
+    ///
+    /// `can_visit(path, next)` decides whether `next` may be appended to the
+    /// path walked so far, without it the search would loop forever on a
+    /// cycle. Paths stop at `end`
+    pub fn paths(
+        &self,
+        start: &N,
+        end: &N,
+        mut can_visit: impl FnMut(&[N], &N) -> bool,
+    ) -> Vec<Vec<N>> {
+        let mut paths = Vec::new();
+        self.walk_paths(start, end, &mut can_visit, &mut |path| {
+            paths.push(path.to_vec())
+        });
+        paths
+    }
+
+    /// Same as [`Graph::paths`] without collecting every path
+    pub fn count_paths(
+        &self,
+        start: &N,
+        end: &N,
+        mut can_visit: impl FnMut(&[N], &N) -> bool,
+    ) -> usize {
+        let mut count = 0;
+        self.walk_paths(start, end, &mut can_visit, &mut |_| count += 1);
+        count
+    }
+
+    /// The cheapest path from `start` to a node matching `is_goal`, along with
+    /// its total weight
+    pub fn dijkstra(&self, start: &N, is_goal: impl Fn(&N) -> bool) -> Option<(u64, Vec<N>)> {
+        self.astar(start, is_goal, |_| 0)
+    }
+
+    /// Same as [`Graph::dijkstra`], exploring the nodes with the lowest
+    /// `cost + heuristic(node)` first
+    ///
+    /// The heuristic must never overestimate the cost left to a goal, otherwise
+    /// the path found might not be the cheapest
+    pub fn astar(
+        &self,
+        start: &N,
+        is_goal: impl Fn(&N) -> bool,
+        heuristic: impl Fn(&N) -> u64,
+    ) -> Option<(u64, Vec<N>)> {
+        let start = *self.index.get(start)?;
+        let mut costs = vec![u64::MAX; self.len()];
+        let mut previous: Vec<Option<usize>> = vec![None; self.len()];
+        let mut queue = BinaryHeap::new();
+        costs[start] = 0;
+        queue.push(Reverse((heuristic(&self.nodes[start]), 0, start)));
+
+        while let Some(Reverse((_, cost, node))) = queue.pop() {
+            if cost > costs[node] {
+                // NOTE, A cheaper way to this node was already explored
+                continue;
+            }
+            if is_goal(&self.nodes[node]) {
+                let mut path = vec![self.nodes[node].clone()];
+                let mut current = node;
+                while let Some(before) = previous[current] {
+                    path.push(self.nodes[before].clone());
+                    current = before;
+                }
+                path.reverse();
+                return Some((cost, path));
+            }
+            for &(next, weight) in &self.edges[node] {
+                let next_cost = cost + weight;
+                if next_cost < costs[next] {
+                    costs[next] = next_cost;
+                    previous[next] = Some(node);
+                    let estimate = next_cost + heuristic(&self.nodes[next]);
+                    queue.push(Reverse((estimate, next_cost, next)));
+                }
+            }
+        }
+        None
+    }
+
+    /// Every node, ordered so that each edge goes from an earlier node to a
+    /// later one (Kahn's algorithm)
+    ///
+    /// Ties are broken by insertion order
+    pub fn topological_sort(&self) -> Result<Vec<N>, CycleError<N>> {
+        let mut in_degree = vec![0; self.len()];
+        for &(to, _) in self.edges.iter().flatten() {
+            in_degree[to] += 1;
+        }
+
+        let mut ready = (0..self.len())
+            .filter(|&n| in_degree[n] == 0)
+            .map(Reverse)
+            .collect::<BinaryHeap<_>>();
+        let mut order = Vec::with_capacity(self.len());
+        while let Some(Reverse(node)) = ready.pop() {
+            order.push(self.nodes[node].clone());
+            for &(next, _) in &self.edges[node] {
+                in_degree[next] -= 1;
+                if in_degree[next] == 0 {
+                    ready.push(Reverse(next));
+                }
+            }
+        }
+
+        if order.len() == self.len() {
+            Ok(order)
+        } else {
+            let nodes = (0..self.len())
+                .filter(|&n| in_degree[n] > 0)
+                .map(|n| self.nodes[n].clone())
+                .collect();
+            Err(CycleError { nodes })
+        }
+    }
+
+    fn id(&mut self, node: N) -> usize {
+        if let Some(&id) = self.index.get(&node) {
+            return id;
+        }
+        let id = self.nodes.len();
+        self.index.insert(node.clone(), id);
+        self.nodes.push(node);
+        self.edges.push(Vec::new());
+        id
+    }
+
+    fn walk_paths(
+        &self,
+        start: &N,
+        end: &N,
+        can_visit: &mut impl FnMut(&[N], &N) -> bool,
+        on_path: &mut impl FnMut(&[N]),
+    ) {
+        let Some(&start) = self.index.get(start) else {
+            return;
+        };
+        let mut path = vec![self.nodes[start].clone()];
+        // NOTE, Each frame is a node on the path and the next edge to try
+        let mut stack = vec![(start, 0)];
+        while let Some((node, edge)) = stack.last_mut() {
+            let Some(&(next, _)) = self.edges[*node].get(*edge) else {
+                stack.pop();
+                path.pop();
+                continue;
+            };
+            *edge += 1;
+
+            let next_node = &self.nodes[next];
+            if !can_visit(&path, next_node) {
+                continue;
+            }
+            path.push(next_node.clone());
+            if next_node == end {
+                on_path(&path);
+                path.pop();
+            } else {
+                stack.push((next, 0));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn caves() -> Graph<&'static str> {
+        let mut graph = Graph::new();
+        for (a, b) in [
+            ("start", "A"),
+            ("start", "b"),
+            ("A", "c"),
+            ("A", "b"),
+            ("b", "d"),
+            ("A", "end"),
+            ("b", "end"),
+        ] {
+            graph.add_undirected_edge(a, b);
+        }
+        graph
+    }
+
+    #[test]
+    fn test_traversals() {
+        let graph = caves();
+        assert_eq!(graph.len(), 6);
+        assert_eq!(
+            graph.bfs(&"start"),
+            vec![
+                ("start", 0),
+                ("A", 1),
+                ("b", 1),
+                ("c", 2),
+                ("end", 2),
+                ("d", 2)
+            ]
+        );
+        assert_eq!(graph.dfs(&"c"), vec!["c", "A", "start", "b", "d", "end"]);
+        assert_eq!(graph.bfs(&"nowhere"), vec![]);
+
+        let small_once = |path: &[&str], next: &&str| {
+            next.chars().all(char::is_uppercase) || !path.contains(next)
+        };
+        assert_eq!(graph.count_paths(&"start", &"end", small_once), 10);
+        let paths = graph.paths(&"start", &"end", small_once);
+        assert_eq!(paths[0], vec!["start", "A", "c", "A", "b", "A", "end"]);
+        assert!(paths.iter().all(|p| p.last() == Some(&"end")));
+    }
+
+    #[test]
+    fn test_shortest_paths() {
+        let mut graph = Graph::new();
+        graph.add_weighted_edge('a', 'b', 7);
+        graph.add_weighted_edge('a', 'c', 2);
+        graph.add_weighted_edge('c', 'b', 3);
+        graph.add_weighted_edge('b', 'd', 1);
+        graph.add_node('e');
+
+        assert_eq!(
+            graph.dijkstra(&'a', |&n| n == 'd'),
+            Some((6, vec!['a', 'c', 'b', 'd']))
+        );
+        assert_eq!(graph.dijkstra(&'a', |&n| n == 'e'), None);
+
+        let heuristic = |n: &char| if *n == 'd' { 0 } else { 1 };
+        assert_eq!(
+            graph.astar(&'a', |&n| n == 'd', heuristic).map(|(c, _)| c),
+            Some(6)
+        );
+    }
+
+    #[test]
+    fn test_topological_sort() {
+        let mut graph = Graph::new();
+        for (before, after) in [(47, 53), (97, 13), (97, 47), (75, 47), (97, 75)] {
+            graph.add_edge(before, after);
+        }
+        assert_eq!(graph.topological_sort(), Ok(vec![97, 13, 75, 47, 53]));
+
+        graph.add_edge(13, 97);
+        graph.add_node(1);
+        let error = graph.topological_sort().unwrap_err();
+        assert_eq!(error.nodes, vec![47, 53, 97, 13, 75]);
+    }
+}
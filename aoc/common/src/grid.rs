@@ -0,0 +1,261 @@
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+};
+
+use crate::{
+    combinators::{self, grid},
+    geom::{Direction4, Direction8, Point},
+    ParseError,
+};
+
+/// A rectangular grid stored row by row
+///
+/// Every lookup by [`Point`] is bounds checked, moving off the grid gives
+/// `None` instead of underflowing or panicking
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// Panics if the rows do not all have the same length
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Self {
+        let width = rows.first().map_or(0, |row| row.len());
+        assert!(
+            rows.iter().all(|row| row.len() == width),
+            "Every row of a grid must have the same length"
+        );
+        Self {
+            width,
+            height: rows.len(),
+            cells: rows.into_iter().flatten().collect(),
+        }
+    }
+
+    /// Parses one row per line, `cell` rejects a character with `None`
+    pub fn parse(
+        input: &str,
+        expected: &str,
+        cell: impl Fn(char) -> Option<T>,
+    ) -> Result<Self, ParseError> {
+        combinators::parse(input, grid(expected, cell)).map(Self::from_rows)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        point.x < self.width && point.y < self.height
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.contains(point)
+            .then(|| &self.cells[point.y * self.width + point.x])
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        if self.contains(point) {
+            Some(&mut self.cells[point.y * self.width + point.x])
+        } else {
+            None
+        }
+    }
+
+    /// The point one `(dx, dy)` step away, if it is on the grid
+    pub fn step(&self, point: Point, delta: (isize, isize)) -> Option<Point> {
+        point.step_within(delta, self.width, self.height)
+    }
+
+    /// Every point of the grid, row by row
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| Point::new(x, y)))
+    }
+
+    /// Every cell along with its point, row by row
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(self.cells.iter())
+    }
+
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.cells.iter_mut()
+    }
+
+    /// Up, right, down and left of `point`, skipping those off the grid
+    pub fn neighbours4(&self, point: Point) -> impl Iterator<Item = Point> {
+        let (width, height) = (self.width, self.height);
+        Direction4::ALL
+            .into_iter()
+            .filter_map(move |d| point.step_within(d.delta(), width, height))
+    }
+
+    /// All 8 points around `point`, skipping those off the grid
+    pub fn neighbours8(&self, point: Point) -> impl Iterator<Item = Point> {
+        let (width, height) = (self.width, self.height);
+        Direction8::ALL
+            .into_iter()
+            .filter_map(move |d| point.step_within(d.delta(), width, height))
+    }
+
+    /// Points from `from` (included) in steps of `delta` until the edge
+    ///
+    /// `(1, 0)` walks along a row, `(0, 1)` down a column and `(1, 1)` along a
+    /// diagonal
+    pub fn line(&self, from: Point, delta: (isize, isize)) -> impl Iterator<Item = Point> {
+        let (width, height) = (self.width, self.height);
+        let from = Some(from).filter(|p| self.contains(*p));
+        std::iter::successors(from, move |p| p.step_within(delta, width, height))
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // NOTE, `chunks` panics on 0, an empty grid has no rows anyway
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// Empty for an `x` outside of the grid
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        let height = if x < self.width { self.height } else { 0 };
+        self.cells
+            .iter()
+            .skip(x)
+            .step_by(self.width.max(1))
+            .take(height)
+    }
+
+    /// The first point holding `value`, row by row
+    pub fn find(&self, value: &T) -> Option<Point>
+    where
+        T: PartialEq,
+    {
+        self.iter().find(|(_, v)| *v == value).map(|(p, _)| p)
+    }
+
+    /// Every point holding `value`, row by row
+    pub fn find_all<'a>(&'a self, value: &'a T) -> impl Iterator<Item = Point> + 'a
+    where
+        T: PartialEq,
+    {
+        self.iter()
+            .filter(move |(_, v)| *v == value)
+            .map(|(p, _)| p)
+    }
+
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        self.get(point)
+            .unwrap_or_else(|| panic!("{:?} is outside of the grid", point))
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        self.get_mut(point)
+            .unwrap_or_else(|| panic!("{:?} is outside of the grid", point))
+    }
+}
+
+/// One line per row, cells are written next to each other
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEIGHTS: &str = "2199943210
+3987894921
+9856789892";
+
+    fn heights() -> Grid<u32> {
+        Grid::parse(HEIGHTS, "a height", |c| c.to_digit(10)).unwrap()
+    }
+
+    #[test]
+    fn test_grid_access() {
+        let mut grid = heights();
+        assert_eq!((grid.width(), grid.height()), (10, 3));
+        assert_eq!(grid[Point::new(1, 0)], 1);
+        assert_eq!(grid.get(Point::new(10, 0)), None);
+        assert_eq!(grid.get(Point::new(0, 3)), None);
+
+        *grid.get_mut(Point::new(0, 0)).unwrap() = 7;
+        grid[Point::new(9, 2)] += 1;
+        assert_eq!(grid.row(0)[0], 7);
+        assert_eq!(grid.column(9).copied().collect::<Vec<_>>(), vec![0, 1, 3]);
+        assert_eq!(grid.column(10).count(), 0);
+        assert_eq!(grid.column(12).count(), 0);
+        assert_eq!(grid.to_string().lines().next(), Some("7199943210"));
+        assert_eq!(grid.rows().count(), 3);
+
+        assert_eq!(grid.find(&9), Some(Point::new(2, 0)));
+        assert_eq!(grid.find_all(&1).count(), 3);
+        assert_eq!(grid.map(|h| *h == 9).find(&true), Some(Point::new(2, 0)));
+
+        let error = Grid::parse("12\n3x", "a height", |c| c.to_digit(10)).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 2));
+    }
+
+    #[test]
+    fn test_grid_neighbours() {
+        let grid = heights();
+        let corner = grid.neighbours4(Point::new(0, 0)).collect::<Vec<_>>();
+        assert_eq!(corner, vec![Point::new(1, 0), Point::new(0, 1)]);
+        assert_eq!(grid.neighbours8(Point::new(9, 2)).count(), 3);
+        assert_eq!(grid.neighbours8(Point::new(4, 1)).count(), 8);
+        assert_eq!(grid.step(Point::new(9, 0), (1, 0)), None);
+
+        let diagonal = grid
+            .line(Point::new(0, 0), (1, 1))
+            .map(|p| grid[p])
+            .collect::<Vec<_>>();
+        assert_eq!(diagonal, vec![2, 9, 5]);
+        let back = grid.line(Point::new(3, 1), (-1, 0)).count();
+        assert_eq!(back, 4);
+        assert_eq!(grid.line(Point::new(10, 0), (1, 0)).count(), 0);
+    }
+}
@@ -0,0 +1,404 @@
+//! PNG and animated GIF export of grids, every cell becomes a square of
+//! pixels
+//!
+//! Everything is encoded in pure Rust, no display or GPU is needed
+
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt, fs,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::{
+    visualize::{Cell, Color},
+    Grid,
+};
+
+/// A colour as red, green and blue
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+
+    /// The colour `t` of the way from `self` to `to`, `t` between 0 and 1
+    pub fn lerp(self, to: Rgb, t: f64) -> Rgb {
+        let t = t.clamp(0.0, 1.0);
+        let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+        Rgb(mix(self.0, to.0), mix(self.1, to.1), mix(self.2, to.2))
+    }
+}
+
+/// The usual xterm values
+impl From<Color> for Rgb {
+    fn from(color: Color) -> Self {
+        match color {
+            Color::Black => Rgb(0, 0, 0),
+            Color::Red => Rgb(255, 0, 0),
+            Color::Green => Rgb(0, 255, 0),
+            Color::Yellow => Rgb(255, 255, 0),
+            Color::Blue => Rgb(92, 92, 255),
+            Color::Magenta => Rgb(255, 0, 255),
+            Color::Cyan => Rgb(0, 255, 255),
+            Color::White => Rgb(255, 255, 255),
+            Color::Rgb(r, g, b) => Rgb(r, g, b),
+        }
+    }
+}
+
+/// Colour of a [`crate::visualize::Frame`] cell
+///
+/// Coloured cells keep their colour. Blank cells (` ` or `.`) are black, the
+/// other symbols are white when bold and grey otherwise
+pub fn cell_color(cell: &Cell) -> Rgb {
+    match (cell.color, cell.symbol, cell.bold) {
+        (Some(color), _, _) => color.into(),
+        (None, ' ' | '.', _) => Rgb::BLACK,
+        (None, _, true) => Rgb::WHITE,
+        (None, _, false) => Rgb(128, 128, 128),
+    }
+}
+
+#[derive(Debug)]
+pub enum ExportError {
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+    Png(png::EncodingError),
+    Gif(gif::EncodingError),
+    /// GIF images can't be wider or higher than 65535 pixels
+    TooLarge {
+        width: usize,
+        height: usize,
+    },
+    /// An animation needs at least one frame
+    NoFrames,
+}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportError::Io { path, source } => {
+                write!(f, "couldn't write {}: {}", path.display(), source)
+            }
+            ExportError::Png(e) => write!(f, "couldn't encode the PNG: {}", e),
+            ExportError::Gif(e) => write!(f, "couldn't encode the GIF: {}", e),
+            ExportError::TooLarge { width, height } => {
+                write!(f, "a {}x{} image is too large for a GIF", width, height)
+            }
+            ExportError::NoFrames => write!(f, "there are no frames to export"),
+        }
+    }
+}
+
+impl Error for ExportError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ExportError::Io { source, .. } => Some(source),
+            ExportError::Png(e) => Some(e),
+            ExportError::Gif(e) => Some(e),
+            ExportError::TooLarge { .. } | ExportError::NoFrames => None,
+        }
+    }
+}
+
+impl From<png::EncodingError> for ExportError {
+    fn from(e: png::EncodingError) -> Self {
+        ExportError::Png(e)
+    }
+}
+
+impl From<gif::EncodingError> for ExportError {
+    fn from(e: gif::EncodingError) -> Self {
+        ExportError::Gif(e)
+    }
+}
+
+/// Turns grids into images, `color` picks the colour of each cell
+///
+/// ```ignore
+/// let exporter = Exporter::new(|&flashed: &bool| if flashed { Rgb::WHITE } else { Rgb::BLACK })
+///     .scale(8)
+///     .delay(Duration::from_millis(50));
+/// exporter.save_gif(Path::new("flashes.gif"), frames)?;
+/// ```
+pub struct Exporter<F> {
+    color: F,
+    scale: usize,
+    delay: Duration,
+}
+
+impl<F> Exporter<F> {
+    /// 4 by 4 pixels per cell and 10 frames per second
+    pub fn new(color: F) -> Self {
+        Self {
+            color,
+            scale: 4,
+            delay: Duration::from_millis(100),
+        }
+    }
+
+    /// Width and height in pixels of a cell
+    pub fn scale(self, scale: usize) -> Self {
+        Self {
+            scale: scale.max(1),
+            ..self
+        }
+    }
+
+    /// How long each frame of a GIF is shown, rounded to 1/100 of a second
+    pub fn delay(self, delay: Duration) -> Self {
+        Self { delay, ..self }
+    }
+
+    pub fn write_png<T>(&self, out: impl Write, frame: &Grid<T>) -> Result<(), ExportError>
+    where
+        F: Fn(&T) -> Rgb,
+    {
+        let (width, height) = (frame.width() * self.scale, frame.height() * self.scale);
+        let mut encoder = png::Encoder::new(out, width as u32, height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+
+        let colors = frame.map(&self.color);
+        let data = self
+            .pixels(&colors, width, height)
+            .flat_map(|Rgb(r, g, b)| [r, g, b])
+            .collect::<Vec<_>>();
+        writer.write_image_data(&data)?;
+        writer.finish()?;
+        Ok(())
+    }
+
+    pub fn save_png<T>(&self, path: &Path, frame: &Grid<T>) -> Result<(), ExportError>
+    where
+        F: Fn(&T) -> Rgb,
+    {
+        self.write_png(create(path)?, frame)
+    }
+
+    /// One `frame_00000.png`, `frame_00001.png`, ... per frame in `dir`,
+    /// returns the number of frames
+    pub fn save_pngs<T>(
+        &self,
+        dir: &Path,
+        frames: impl IntoIterator<Item = Grid<T>>,
+    ) -> Result<usize, ExportError>
+    where
+        F: Fn(&T) -> Rgb,
+    {
+        fs::create_dir_all(dir).map_err(|source| ExportError::Io {
+            path: dir.to_path_buf(),
+            source,
+        })?;
+        let mut count = 0;
+        for frame in frames {
+            self.save_png(&dir.join(format!("frame_{:05}.png", count)), &frame)?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// An animated GIF looping over every frame, returns the number of frames
+    ///
+    /// The image has the size of the first frame, larger frames are cropped
+    /// to it
+    pub fn write_gif<T>(
+        &self,
+        out: impl Write,
+        frames: impl IntoIterator<Item = Grid<T>>,
+    ) -> Result<usize, ExportError>
+    where
+        F: Fn(&T) -> Rgb,
+    {
+        let mut frames = frames.into_iter().peekable();
+        let first = frames.peek().ok_or(ExportError::NoFrames)?;
+        let (width, height) = (first.width() * self.scale, first.height() * self.scale);
+        let (Ok(gif_width), Ok(gif_height)) = (u16::try_from(width), u16::try_from(height)) else {
+            return Err(ExportError::TooLarge { width, height });
+        };
+
+        let mut encoder = gif::Encoder::new(out, gif_width, gif_height, &[])?;
+        encoder.set_repeat(gif::Repeat::Infinite)?;
+        let delay = (self.delay.as_millis() / 10).min(u16::MAX as u128) as u16;
+        let mut count = 0;
+        for frame in frames {
+            let colors = frame.map(&self.color);
+            let width = width.min(colors.width() * self.scale);
+            let height = height.min(colors.height() * self.scale);
+            let mut gif_frame = self.gif_frame(&colors, width, height);
+            gif_frame.delay = delay;
+            // NOTE, Clears whatever a larger previous frame left behind
+            gif_frame.dispose = gif::DisposalMethod::Background;
+            encoder.write_frame(&gif_frame)?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    pub fn save_gif<T>(
+        &self,
+        path: &Path,
+        frames: impl IntoIterator<Item = Grid<T>>,
+    ) -> Result<usize, ExportError>
+    where
+        F: Fn(&T) -> Rgb,
+    {
+        self.write_gif(create(path)?, frames)
+    }
+
+    /// The top left `width` by `height` pixels of the scaled up grid, row by
+    /// row
+    fn pixels<'a>(
+        &self,
+        colors: &'a Grid<Rgb>,
+        width: usize,
+        height: usize,
+    ) -> impl Iterator<Item = Rgb> + 'a {
+        let scale = self.scale;
+        (0..height).flat_map(move |y| {
+            let row = colors.row(y / scale);
+            (0..width).map(move |x| row[x / scale])
+        })
+    }
+
+    /// Exact colours with a local palette when a frame has at most 256 of
+    /// them, quantized otherwise
+    fn gif_frame(&self, colors: &Grid<Rgb>, width: usize, height: usize) -> gif::Frame<'static> {
+        let mut palette = HashMap::new();
+        for (_, &color) in colors.iter() {
+            let next = palette.len();
+            palette.entry(color).or_insert(next);
+        }
+
+        if palette.len() <= 256 {
+            let indices = self
+                .pixels(colors, width, height)
+                .map(|color| palette[&color] as u8)
+                .collect::<Vec<_>>();
+            let mut entries = vec![Rgb::BLACK; palette.len()];
+            for (color, index) in palette {
+                entries[index] = color;
+            }
+            let entries = entries
+                .into_iter()
+                .flat_map(|Rgb(r, g, b)| [r, g, b])
+                .collect::<Vec<_>>();
+            gif::Frame::from_palette_pixels(width as u16, height as u16, indices, entries, None)
+        } else {
+            let data = self
+                .pixels(colors, width, height)
+                .flat_map(|Rgb(r, g, b)| [r, g, b])
+                .collect::<Vec<_>>();
+            gif::Frame::from_rgb_speed(width as u16, height as u16, &data, 10)
+        }
+    }
+}
+
+fn create(path: &Path) -> Result<BufWriter<fs::File>, ExportError> {
+    fs::File::create(path)
+        .map(BufWriter::new)
+        .map_err(|source| ExportError::Io {
+            path: path.to_path_buf(),
+            source,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Point;
+
+    use super::*;
+
+    fn checkerboard(size: usize) -> Grid<bool> {
+        let mut grid = Grid::new(size, size, false);
+        for point in grid.points().collect::<Vec<_>>() {
+            grid[point] = (point.x + point.y) % 2 == 0;
+        }
+        grid
+    }
+
+    fn black_and_white(&on: &bool) -> Rgb {
+        if on {
+            Rgb::WHITE
+        } else {
+            Rgb::BLACK
+        }
+    }
+
+    #[test]
+    fn test_png() {
+        let exporter = Exporter::new(black_and_white).scale(3);
+        let mut out = Vec::new();
+        exporter.write_png(&mut out, &checkerboard(2)).unwrap();
+
+        let mut reader = png::Decoder::new(out.as_slice()).read_info().unwrap();
+        let mut data = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut data).unwrap();
+        assert_eq!((info.width, info.height), (6, 6));
+        assert_eq!(info.color_type, png::ColorType::Rgb);
+        let pixel = |x: usize, y: usize| data[(y * 6 + x) * 3];
+        assert_eq!(
+            [pixel(0, 0), pixel(2, 2), pixel(3, 0), pixel(5, 5)],
+            [255, 255, 0, 255]
+        );
+    }
+
+    #[test]
+    fn test_gif() {
+        let exporter = Exporter::new(black_and_white).delay(Duration::from_millis(50));
+        let frames = (1..=3).rev().map(checkerboard);
+        let mut out = Vec::new();
+        assert_eq!(exporter.write_gif(&mut out, frames).unwrap(), 3);
+
+        let mut options = gif::DecodeOptions::new();
+        options.set_color_output(gif::ColorOutput::RGBA);
+        let mut decoder = options.read_info(out.as_slice()).unwrap();
+        assert_eq!((decoder.width(), decoder.height()), (12, 12));
+        let mut sizes = Vec::new();
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            assert_eq!(frame.delay, 5);
+            assert_eq!(&frame.buffer[..4], &[255, 255, 255, 255]);
+            sizes.push((frame.width, frame.height));
+        }
+        assert_eq!(sizes, vec![(12, 12), (8, 8), (4, 4)]);
+
+        let none = std::iter::empty::<Grid<bool>>();
+        assert!(matches!(
+            exporter.write_gif(Vec::new(), none),
+            Err(ExportError::NoFrames)
+        ));
+        let huge = Grid::new(20_000, 1, false);
+        assert!(matches!(
+            exporter.write_gif(Vec::new(), [huge]),
+            Err(ExportError::TooLarge { .. })
+        ));
+    }
+
+    #[test]
+    fn test_many_colors_are_quantized() {
+        let mut gradient = Grid::new(300, 1, 0);
+        for x in 0..300 {
+            gradient[Point::new(x, 0)] = x;
+        }
+        let exporter = Exporter::new(|&x: &usize| Rgb::BLACK.lerp(Rgb::WHITE, x as f64 / 299.0));
+        let mut out = Vec::new();
+        assert_eq!(exporter.write_gif(&mut out, [gradient]).unwrap(), 1);
+        assert!(gif::DecodeOptions::new().read_info(out.as_slice()).is_ok());
+
+        assert_eq!(
+            Rgb(0, 100, 200).lerp(Rgb(100, 100, 0), 0.5),
+            Rgb(50, 100, 100)
+        );
+        let cell = Cell::colored('x', Color::Rgb(1, 2, 3));
+        assert_eq!(cell_color(&cell), Rgb(1, 2, 3));
+        assert_eq!(cell_color(&Cell::new('.')), Rgb::BLACK);
+        assert_eq!(cell_color(&Cell::new('#').bold()), Rgb::WHITE);
+    }
+}
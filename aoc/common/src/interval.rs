@@ -0,0 +1,318 @@
+//! Ranges of integers and sets of them
+//!
+//! Intervals are half open, `[start, end)`, so that splitting and merging never
+//! needs a `+ 1` or `- 1`. Puzzle inputs usually give both ends, see
+//! [`Interval::inclusive`]
+
+use std::ops::{Add, Sub};
+
+/// Integers an [`Interval`] can be made of
+pub trait Bound: Copy + Ord + Add<Output = Self> + Sub<Output = Self> + From<u8> {
+    fn checked_add(self, other: Self) -> Option<Self>;
+}
+
+macro_rules! impl_bound {
+    ($($t:ty),*) => {
+        $(impl Bound for $t {
+            fn checked_add(self, other: Self) -> Option<Self> {
+                <$t>::checked_add(self, other)
+            }
+        })*
+    };
+}
+
+impl_bound!(u8, u16, u32, u64, u128, usize, i16, i32, i64, i128, isize);
+
+/// Every integer from `start` up to, but not including, `end`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Interval<T> {
+    pub start: T,
+    pub end: T,
+}
+
+impl<T: Bound> Interval<T> {
+    pub fn new(start: T, end: T) -> Self {
+        Self { start, end }
+    }
+
+    /// Every integer from `first` to `last`, both included, e.g. `2-4`
+    ///
+    /// `None` when `last` is the largest `T`, the end wouldn't fit. A `last`
+    /// before `first` gives an empty interval
+    pub fn inclusive(first: T, last: T) -> Option<Self> {
+        Some(Self::new(first, last.checked_add(T::from(1))?))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.end <= self.start
+    }
+
+    /// Number of integers in the interval
+    pub fn len(&self) -> T {
+        if self.is_empty() {
+            T::from(0)
+        } else {
+            self.end - self.start
+        }
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.start <= value && value < self.end
+    }
+
+    /// Every integer of `other` is in this interval too
+    pub fn contains_interval(&self, other: &Interval<T>) -> bool {
+        other.is_empty() || (self.start <= other.start && other.end <= self.end)
+    }
+
+    /// Both intervals have at least one integer in common
+    pub fn overlaps(&self, other: &Interval<T>) -> bool {
+        !self.intersection(other).is_empty()
+    }
+
+    /// The integers in both intervals, which might be empty
+    pub fn intersection(&self, other: &Interval<T>) -> Interval<T> {
+        Interval::new(self.start.max(other.start), self.end.min(other.end))
+    }
+}
+
+/// A set of integers, stored as sorted intervals that neither overlap nor touch
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self {
+            intervals: Vec::new(),
+        }
+    }
+}
+
+impl<T: Bound> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Number of integers in the set
+    pub fn len(&self) -> T {
+        self.intervals
+            .iter()
+            .fold(T::from(0), |total, interval| total + interval.len())
+    }
+
+    /// The disjoint intervals making up the set, in increasing order
+    pub fn intervals(&self) -> &[Interval<T>] {
+        &self.intervals
+    }
+
+    pub fn insert(&mut self, interval: Interval<T>) {
+        if interval.is_empty() {
+            return;
+        }
+        // NOTE, Every interval touching the new one gets merged into it
+        let first = self.intervals.partition_point(|i| i.end < interval.start);
+        let last = self.intervals.partition_point(|i| i.start <= interval.end);
+        let merged = self.intervals[first..last]
+            .iter()
+            .fold(interval, |merged, i| {
+                Interval::new(merged.start.min(i.start), merged.end.max(i.end))
+            });
+        self.intervals.splice(first..last, [merged]);
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let index = self.intervals.partition_point(|i| i.end <= value);
+        self.intervals
+            .get(index)
+            .is_some_and(|interval| interval.contains(value))
+    }
+
+    /// Every integer of `interval` is in the set
+    pub fn contains_interval(&self, interval: &Interval<T>) -> bool {
+        interval.is_empty()
+            || self
+                .overlapping(interval)
+                .next()
+                .is_some_and(|i| i.contains_interval(interval))
+    }
+
+    /// The intervals of the set that share at least one integer with `interval`
+    pub fn overlapping<'a>(
+        &'a self,
+        interval: &'a Interval<T>,
+    ) -> impl Iterator<Item = &'a Interval<T>> + 'a {
+        let first = self.intervals.partition_point(|i| i.end <= interval.start);
+        self.intervals[first..]
+            .iter()
+            .take_while(move |i| i.start < interval.end)
+            .filter(move |i| i.overlaps(interval))
+    }
+
+    pub fn union(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut intervals = self.intervals.clone();
+        intervals.extend_from_slice(&other.intervals);
+        IntervalSet::from_iter(intervals)
+    }
+
+    pub fn intersection(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut intervals = Vec::new();
+        let (mut a, mut b) = (0, 0);
+        while let (Some(x), Some(y)) = (self.intervals.get(a), other.intervals.get(b)) {
+            let common = x.intersection(y);
+            if !common.is_empty() {
+                intervals.push(common);
+            }
+            // NOTE, The interval ending first can't overlap anything else
+            if x.end < y.end {
+                a += 1;
+            } else {
+                b += 1;
+            }
+        }
+        IntervalSet { intervals }
+    }
+
+    /// The integers of this set that are not in `other`
+    pub fn difference(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut intervals = Vec::new();
+        let mut removed = other.intervals.iter().peekable();
+        for interval in &self.intervals {
+            let mut start = interval.start;
+            while let Some(cut) = removed.peek() {
+                if cut.end <= start {
+                    removed.next();
+                    continue;
+                }
+                if cut.start >= interval.end {
+                    break;
+                }
+                if cut.start > start {
+                    intervals.push(Interval::new(start, cut.start));
+                }
+                start = cut.end;
+                if cut.end > interval.end {
+                    break;
+                }
+                removed.next();
+            }
+            if start < interval.end {
+                intervals.push(Interval::new(start, interval.end));
+            }
+        }
+        IntervalSet { intervals }
+    }
+}
+
+/// Merges any number of intervals in `O(n log n)`
+impl<T: Bound> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> Self {
+        let mut sorted = iter
+            .into_iter()
+            .filter(|i| !i.is_empty())
+            .collect::<Vec<_>>();
+        sorted.sort_unstable_by_key(|i| i.start);
+
+        let mut intervals: Vec<Interval<T>> = Vec::with_capacity(sorted.len());
+        for interval in sorted {
+            match intervals.last_mut() {
+                Some(last) if interval.start <= last.end => last.end = last.end.max(interval.end),
+                _ => intervals.push(interval),
+            }
+        }
+        IntervalSet { intervals }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(intervals: &[(i64, i64)]) -> IntervalSet<i64> {
+        intervals
+            .iter()
+            .map(|&(start, end)| Interval::new(start, end))
+            .collect()
+    }
+
+    #[test]
+    fn test_interval() {
+        let a = Interval::inclusive(2u32, 8).unwrap();
+        let b = Interval::inclusive(3u32, 7).unwrap();
+        assert_eq!((a.len(), b.len()), (7, 5));
+        assert!(a.contains(8) && !a.contains(9));
+        assert!(a.contains_interval(&b) && !b.contains_interval(&a));
+        assert!(a.overlaps(&b));
+
+        let c = Interval::inclusive(5u32, 7).unwrap();
+        let d = Interval::inclusive(7u32, 9).unwrap();
+        assert_eq!(c.intersection(&d), Interval::inclusive(7, 7).unwrap());
+        assert!(!Interval::inclusive(2u32, 4)
+            .unwrap()
+            .overlaps(&Interval::inclusive(5, 6).unwrap()));
+        assert!(Interval::new(4u32, 2).is_empty());
+        assert_eq!(Interval::new(4u32, 2).len(), 0);
+
+        assert_eq!(Interval::inclusive(0u8, 255), None);
+        assert_eq!(Interval::inclusive(0u8, 254).unwrap().len(), 255);
+        assert!(Interval::inclusive(5u32, 3).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_interval_set() {
+        let mut merged = set(&[(10, 14), (0, 3), (3, 5), (12, 20), (-4, -2), (7, 7)]);
+        assert_eq!(merged, set(&[(-4, -2), (0, 5), (10, 20)]));
+        assert_eq!(merged.intervals().len(), 3);
+        assert_eq!(merged.len(), 17);
+        assert!(merged.contains(-4) && merged.contains(4) && merged.contains(19));
+        assert!(!merged.contains(-2) && !merged.contains(5) && !merged.contains(20));
+        assert!(merged.contains_interval(&Interval::new(11, 20)));
+        assert!(!merged.contains_interval(&Interval::new(4, 11)));
+        let overlapping = merged.overlapping(&Interval::new(4, 11)).count();
+        assert_eq!(overlapping, 2);
+
+        merged.insert(Interval::new(5, 10));
+        assert_eq!(merged, set(&[(-4, -2), (0, 20)]));
+        merged.insert(Interval::new(-10, -8));
+        assert_eq!(merged, set(&[(-10, -8), (-4, -2), (0, 20)]));
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = set(&[(0, 10), (20, 30)]);
+        let b = set(&[(5, 25), (28, 40)]);
+        assert_eq!(a.union(&b), set(&[(0, 40)]));
+        assert_eq!(a.intersection(&b), set(&[(5, 10), (20, 25), (28, 30)]));
+        assert_eq!(a.difference(&b), set(&[(0, 5), (25, 28)]));
+        assert_eq!(b.difference(&a), set(&[(10, 20), (30, 40)]));
+        assert_eq!(
+            a.difference(&set(&[(2, 4), (6, 8)])),
+            set(&[(0, 2), (4, 6), (8, 10), (20, 30)])
+        );
+        assert_eq!(a.difference(&set(&[(-5, 50)])), IntervalSet::new());
+        assert_eq!(a.intersection(&IntervalSet::new()), IntervalSet::new());
+    }
+
+    #[test]
+    fn test_merge_many() {
+        // NOTE, Every odd number and the even numbers around it
+        let intervals = (0..1_000_000i64)
+            .rev()
+            .map(|i| Interval::new(2 * i, 2 * i + 1));
+        let mut set = intervals.collect::<IntervalSet<_>>();
+        assert_eq!(set.intervals().len(), 1_000_000);
+        assert_eq!(set.len(), 1_000_000);
+
+        set = set.union(
+            &(0..1_000_000i64)
+                .map(|i| Interval::new(2 * i + 1, 2 * i + 2))
+                .collect(),
+        );
+        assert_eq!(set, IntervalSet::from_iter([Interval::new(0, 2_000_000)]));
+    }
+}
@@ -0,0 +1,91 @@
+pub mod combinators;
+pub mod csp;
+pub mod delimiters;
+pub mod diagnostics;
+mod error;
+pub mod examples;
+pub mod geom;
+pub mod graph;
+pub mod grid;
+pub mod image;
+pub mod interval;
+pub mod ocr;
+pub mod parse;
+pub mod rewrite;
+pub mod simulation;
+mod solution;
+pub mod timing;
+pub mod visualize;
+
+pub use common_macros::{aoc, visualize};
+pub use error::{Answer, AocError};
+pub use geom::Point;
+pub use grid::Grid;
+pub use parse::{ParseError, Span};
+pub use simulation::Simulation;
+pub use solution::{
+    assert_implementations_agree, Mismatch, Outcome, Registry, Solution, SolutionFn,
+};
+
+#[doc(hidden)]
+pub use inventory;
+
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Root of the workspace, the folder holding one directory per year
+pub fn workspace_dir() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap()
+}
+
+/// Location of the puzzle input for `year` and `day`
+///
+/// `$AOC_INPUT_DIR/<year>/dayN_input.txt` when `AOC_INPUT_DIR` is set,
+/// `<workspace>/<year>/inputs/dayN_input.txt` otherwise, so the result does
+/// not depend on the directory the runner was launched from
+pub fn input_path(year: u32, day: u32) -> PathBuf {
+    let filename = format!("day{}_input.txt", day);
+    match env::var_os("AOC_INPUT_DIR") {
+        Some(dir) => Path::new(&dir).join(year.to_string()).join(filename),
+        None => workspace_dir()
+            .join(year.to_string())
+            .join("inputs")
+            .join(filename),
+    }
+}
+
+pub fn read_file(path: &Path) -> Result<String, AocError> {
+    fs::read_to_string(path).map_err(|source| match source.kind() {
+        io::ErrorKind::NotFound => AocError::MissingInput(path.to_path_buf()),
+        _ => AocError::Io {
+            path: path.to_path_buf(),
+            source,
+        },
+    })
+}
+
+/// Reads the puzzle input for `year` and `day`, see [`input_path`]
+pub fn read_input(year: u32, day: u32) -> Result<String, AocError> {
+    read_file(&input_path(year, day))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_file_errors() {
+        let path = workspace_dir()
+            .join("2021")
+            .join("inputs")
+            .join("day0_input.txt");
+        assert!(matches!(read_file(&path), Err(AocError::MissingInput(p)) if p == path));
+
+        let dir = workspace_dir().join("2021");
+        assert!(matches!(read_file(&dir), Err(AocError::Io { .. })));
+
+        assert!(read_input(2021, 1).unwrap().starts_with("1"));
+    }
+}
@@ -0,0 +1,319 @@
+//! Reads the block capital letters some puzzles draw instead of giving a
+//! number, e.g. the folded paper of 2021 day 13
+//!
+//! Two alphabets exist, letters 6 pixels high (usually 4 wide) and letters 10
+//! pixels high (usually 6 wide). Letters are told apart by the empty columns
+//! between them, so the few wider or narrower glyphs are fine too
+
+use std::{error::Error, fmt};
+
+use crate::{Grid, Point};
+
+/// Letters 6 pixels high
+const SMALL: &[(char, [&str; 6])] = &[
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', ["###", ".#.", ".#.", ".#.", ".#.", "###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', ["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+/// Letters 10 pixels high
+const LARGE: &[(char, [&str; 10])] = &[
+    (
+        'A',
+        [
+            "..##..", ".#..#.", "#....#", "#....#", "#....#", "######", "#....#", "#....#",
+            "#....#", "#....#",
+        ],
+    ),
+    (
+        'B',
+        [
+            "#####.", "#....#", "#....#", "#....#", "#####.", "#....#", "#....#", "#....#",
+            "#....#", "#####.",
+        ],
+    ),
+    (
+        'C',
+        [
+            ".####.", "#....#", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....",
+            "#....#", ".####.",
+        ],
+    ),
+    (
+        'E',
+        [
+            "######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....",
+            "#.....", "######",
+        ],
+    ),
+    (
+        'F',
+        [
+            "######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....",
+            "#.....", "#.....",
+        ],
+    ),
+    (
+        'G',
+        [
+            ".####.", "#....#", "#.....", "#.....", "#.....", "#..###", "#....#", "#....#",
+            "#...##", ".###.#",
+        ],
+    ),
+    (
+        'H',
+        [
+            "#....#", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#",
+            "#....#", "#....#",
+        ],
+    ),
+    (
+        'J',
+        [
+            "...###", "....#.", "....#.", "....#.", "....#.", "....#.", "....#.", "#...#.",
+            "#...#.", ".###..",
+        ],
+    ),
+    (
+        'K',
+        [
+            "#....#", "#...#.", "#..#..", "#.#...", "##....", "##....", "#.#...", "#..#..",
+            "#...#.", "#....#",
+        ],
+    ),
+    (
+        'L',
+        [
+            "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....",
+            "#.....", "######",
+        ],
+    ),
+    (
+        'N',
+        [
+            "#....#", "##...#", "##...#", "#.#..#", "#.#..#", "#..#.#", "#..#.#", "#...##",
+            "#...##", "#....#",
+        ],
+    ),
+    (
+        'P',
+        [
+            "#####.", "#....#", "#....#", "#....#", "#####.", "#.....", "#.....", "#.....",
+            "#.....", "#.....",
+        ],
+    ),
+    (
+        'R',
+        [
+            "#####.", "#....#", "#....#", "#....#", "#####.", "#..#..", "#...#.", "#...#.",
+            "#....#", "#....#",
+        ],
+    ),
+    (
+        'X',
+        [
+            "#....#", "#....#", ".#..#.", ".#..#.", "..##..", "..##..", ".#..#.", ".#..#.",
+            "#....#", "#....#",
+        ],
+    ),
+    (
+        'Z',
+        [
+            "######", ".....#", ".....#", "....#.", "...#..", "..#...", ".#....", "#.....",
+            "#.....", "######",
+        ],
+    ),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OcrError {
+    /// Nothing is drawn at all
+    Empty,
+    /// The letters are neither 6 nor 10 pixels high
+    UnsupportedHeight(usize),
+    /// The letter at `index` (from 0) isn't in the alphabet, `glyph` is what
+    /// it looks like with one line per row
+    UnknownGlyph { index: usize, glyph: String },
+}
+
+impl fmt::Display for OcrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OcrError::Empty => write!(f, "there are no letters to read"),
+            OcrError::UnsupportedHeight(height) => write!(
+                f,
+                "letters are 6 or 10 pixels high, these are {} pixels high",
+                height
+            ),
+            OcrError::UnknownGlyph { index, glyph } => {
+                write!(f, "letter {} is unknown:\n{}", index + 1, glyph)
+            }
+        }
+    }
+}
+
+impl Error for OcrError {}
+
+/// Reads the letters drawn by every `true` cell
+pub fn read_grid(grid: &Grid<bool>) -> Result<String, OcrError> {
+    read_points(grid.find_all(&true))
+}
+
+/// Reads the letters drawn by `points`, wherever they are on the plane
+pub fn read_points(points: impl IntoIterator<Item = Point>) -> Result<String, OcrError> {
+    let points = points.into_iter().collect::<Vec<_>>();
+    let (Some(left), Some(top)) = (
+        points.iter().map(|p| p.x).min(),
+        points.iter().map(|p| p.y).min(),
+    ) else {
+        return Err(OcrError::Empty);
+    };
+    let right = points.iter().map(|p| p.x).max().unwrap();
+    let bottom = points.iter().map(|p| p.y).max().unwrap();
+
+    let mut pixels = Grid::new(right - left + 1, bottom - top + 1, false);
+    for p in points {
+        pixels[Point::new(p.x - left, p.y - top)] = true;
+    }
+
+    let height = pixels.height();
+    let glyphs = match height {
+        6 => SMALL
+            .iter()
+            .map(|(c, rows)| (*c, &rows[..]))
+            .collect::<Vec<_>>(),
+        10 => LARGE.iter().map(|(c, rows)| (*c, &rows[..])).collect(),
+        _ => return Err(OcrError::UnsupportedHeight(height)),
+    };
+
+    letters(&pixels)
+        .enumerate()
+        .map(|(index, rows)| {
+            glyphs
+                .iter()
+                .find(|(_, glyph)| *glyph == rows.as_slice())
+                .map(|(c, _)| *c)
+                .ok_or_else(|| OcrError::UnknownGlyph {
+                    index,
+                    glyph: rows.join("\n"),
+                })
+        })
+        .collect()
+}
+
+/// The rows of each run of non empty columns, as `#` and `.`
+fn letters(pixels: &Grid<bool>) -> impl Iterator<Item = Vec<String>> + '_ {
+    let empty = (0..pixels.width())
+        .map(|x| pixels.column(x).all(|&lit| !lit))
+        .collect::<Vec<_>>();
+    let mut x = 0;
+    std::iter::from_fn(move || {
+        while empty.get(x) == Some(&true) {
+            x += 1;
+        }
+        let start = x;
+        while empty.get(x) == Some(&false) {
+            x += 1;
+        }
+        (start < x).then(|| {
+            pixels
+                .rows()
+                .map(|row| {
+                    row[start..x]
+                        .iter()
+                        .map(|&lit| if lit { '#' } else { '.' })
+                        .collect()
+                })
+                .collect()
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(art: &[&str]) -> Grid<bool> {
+        Grid::parse(&art.join("\n"), "`#` or `.`", |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_small_letters() {
+        let code = parse(&[
+            "###..####.###...##...##....##.###..###.",
+            "#..#.#....#..#.#..#.#..#....#.#..#.#..#",
+            "#..#.###..#..#.#....#.......#.#..#.###.",
+            "###..#....###..#....#.##....#.###..#..#",
+            "#....#....#.#..#..#.#..#.#..#.#....#..#",
+            "#....####.#..#..##...###..##..#....###.",
+        ]);
+        assert_eq!(read_grid(&code), Ok("PERCGJPB".to_string()));
+
+        // NOTE, The whole alphabet, only in the order of the table
+        let width = SMALL
+            .iter()
+            .map(|(_, rows)| rows[0].len() + 1)
+            .sum::<usize>();
+        let mut alphabet = Grid::new(width, 6, false);
+        let mut x = 0;
+        for (_, rows) in SMALL {
+            for (y, row) in rows.iter().enumerate() {
+                for (dx, c) in row.chars().enumerate() {
+                    alphabet[Point::new(x + dx, y)] = c == '#';
+                }
+            }
+            x += rows[0].len() + 1;
+        }
+        assert_eq!(read_grid(&alphabet), Ok("ABCEFGHIJKLOPRSUYZ".to_string()));
+    }
+
+    #[test]
+    fn test_large_letters() {
+        let points = LARGE
+            .iter()
+            .filter(|(c, _)| "HXZ".contains(*c))
+            .enumerate()
+            .flat_map(|(i, (_, rows))| {
+                rows.iter().enumerate().flat_map(move |(y, row)| {
+                    row.char_indices()
+                        .filter(|&(_, c)| c == '#')
+                        .map(move |(x, _)| Point::new(100 + i * 8 + x, 50 + y))
+                })
+            });
+        assert_eq!(read_points(points), Ok("HXZ".to_string()));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(read_points([]), Err(OcrError::Empty));
+        let square = parse(&["#####", "#...#", "#...#", "#...#", "#####"]);
+        assert_eq!(read_grid(&square), Err(OcrError::UnsupportedHeight(5)));
+
+        let unknown = parse(&["#..#", "####", "#..#", "#..#", "#..#", "#..#"]);
+        let error = read_grid(&unknown).unwrap_err();
+        assert!(matches!(error, OcrError::UnknownGlyph { index: 0, .. }));
+        assert!(error
+            .to_string()
+            .ends_with("#..#\n####\n#..#\n#..#\n#..#\n#..#"));
+    }
+}
@@ -0,0 +1,264 @@
+use std::{any, error::Error, fmt, str::FromStr};
+
+/// A malformed piece of puzzle input, with where it was found and what should
+/// have been there instead
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based line of the offending text
+    pub line: usize,
+    /// 1-based column of the offending text, in characters
+    pub column: usize,
+    pub text: String,
+    pub expected: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: expected {}, ",
+            self.line, self.column, self.expected
+        )?;
+        // NOTE, Only the first line is shown, spans can cover a whole block
+        let first_line = self.text.lines().next().unwrap_or_default();
+        match first_line.char_indices().nth(40) {
+            _ if self.text.is_empty() => write!(f, "found nothing"),
+            Some((end, _)) => write!(f, "found {:?}...", &first_line[..end]),
+            None if first_line.len() < self.text.len() => write!(f, "found {:?}...", first_line),
+            None => write!(f, "found {:?}", first_line),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// A slice of the puzzle input that knows its line and column
+///
+/// Every way of cutting a span into smaller spans keeps track of the position,
+/// so an error can be raised from any sub span with [`Span::error`]
+///
+/// Spans only know their offset in the input, the line and column are counted
+/// when asked for, so cutting spans stays cheap and only errors pay for it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span<'a> {
+    /// The whole input
+    input: &'a str,
+    /// Byte offset of the span in `input`
+    offset: usize,
+    text: &'a str,
+}
+
+impl<'a> Span<'a> {
+    /// The whole input, starting at line 1, column 1
+    pub fn new(input: &'a str) -> Self {
+        Self {
+            input,
+            offset: 0,
+            text: input,
+        }
+    }
+
+    pub fn as_str(&self) -> &'a str {
+        self.text
+    }
+
+    pub fn line(&self) -> usize {
+        self.position().0
+    }
+
+    pub fn column(&self) -> usize {
+        self.position().1
+    }
+
+    /// 1-based line and column, counted from the start of the input
+    fn position(&self) -> (usize, usize) {
+        let before = &self.input[..self.offset];
+        let line_start = before.rfind('\n').map_or(0, |newline| newline + 1);
+        (
+            before.matches('\n').count() + 1,
+            before[line_start..].chars().count() + 1,
+        )
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// `expected` was not found, this span was found instead
+    pub fn error(&self, expected: impl Into<String>) -> ParseError {
+        let (line, column) = self.position();
+        ParseError {
+            line,
+            column,
+            text: self.text.to_string(),
+            expected: expected.into(),
+        }
+    }
+
+    /// The empty span right after this one, for input that ends too early
+    pub fn end(&self) -> Span<'a> {
+        self.slice(self.text.len(), self.text.len())
+    }
+
+    pub fn trim(&self) -> Span<'a> {
+        self.sub(self.text.trim())
+    }
+
+    pub fn lines(&self) -> impl Iterator<Item = Span<'a>> {
+        let this = *self;
+        self.text.lines().map(move |l| this.sub(l))
+    }
+
+    pub fn split(&self, delimiter: &'a str) -> impl Iterator<Item = Span<'a>> {
+        let this = *self;
+        self.text.split(delimiter).map(move |s| this.sub(s))
+    }
+
+    pub fn split_whitespace(&self) -> impl Iterator<Item = Span<'a>> {
+        let this = *self;
+        self.text.split_whitespace().map(move |s| this.sub(s))
+    }
+
+    /// Blocks of lines separated by one or more blank lines
+    pub fn sections(&self) -> impl Iterator<Item = Span<'a>> {
+        let mut sections = Vec::new();
+        let mut current: Option<(usize, usize)> = None;
+        let mut offset = 0;
+        for line in self.text.split_inclusive('\n') {
+            let content = line.trim_end_matches(['\n', '\r']);
+            if content.trim().is_empty() {
+                sections.extend(current.take());
+            } else {
+                let (start, _) = current.unwrap_or((offset, offset));
+                current = Some((start, offset + content.len()));
+            }
+            offset += line.len();
+        }
+        sections.extend(current);
+
+        let this = *self;
+        sections
+            .into_iter()
+            .map(move |(start, end)| this.slice(start, end))
+    }
+
+    /// Every character along with its own span
+    pub fn chars(&self) -> impl Iterator<Item = (Span<'a>, char)> {
+        let this = *self;
+        self.text
+            .char_indices()
+            .map(move |(i, c)| (this.slice(i, i + c.len_utf8()), c))
+    }
+
+    pub fn split_once(&self, delimiter: &str) -> Result<(Span<'a>, Span<'a>), ParseError> {
+        match self.text.split_once(delimiter) {
+            Some((before, after)) => Ok((self.sub(before), self.sub(after))),
+            None => Err(self.error(format!("`{}`", delimiter))),
+        }
+    }
+
+    pub fn strip_prefix(&self, prefix: &str) -> Result<Span<'a>, ParseError> {
+        match self.text.strip_prefix(prefix) {
+            Some(rest) => Ok(self.sub(rest)),
+            None => Err(self.error(format!("`{}`", prefix))),
+        }
+    }
+
+    /// Parses the whole span, the error names the type that was expected
+    pub fn parse<T: FromStr>(&self) -> Result<T, ParseError> {
+        self.text.parse::<T>().map_err(|_| {
+            let name = any::type_name::<T>();
+            self.error(name.rsplit("::").next().unwrap_or(name))
+        })
+    }
+
+    /// Sub span from byte `start` to byte `end` of this span
+    fn slice(&self, start: usize, end: usize) -> Span<'a> {
+        Span {
+            input: self.input,
+            offset: self.offset + start,
+            text: &self.text[start..end],
+        }
+    }
+
+    /// Sub span for `sub`, which must point into this span
+    fn sub(&self, sub: &'a str) -> Span<'a> {
+        let start = sub.as_ptr() as usize - self.text.as_ptr() as usize;
+        debug_assert!(start + sub.len() <= self.text.len());
+        self.slice(start, start + sub.len())
+    }
+}
+
+impl fmt::Display for Span<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_span_positions() {
+        let input = Span::new("0,9 -> 5,9\n  8,0 => 0,8\n\nend");
+        let lines = input.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 4);
+        assert_eq!((lines[1].line(), lines[1].column()), (2, 1));
+
+        let (from, to) = lines[0].split_once("->").unwrap();
+        assert_eq!((from.trim().as_str(), to.trim().column()), ("0,9", 8));
+
+        let error = lines[1].trim().split_once("->").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 3));
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 3: expected `->`, found \"8,0 => 0,8\""
+        );
+
+        let blocks = input.split("\n\n").collect::<Vec<_>>();
+        assert_eq!((blocks[1].line(), blocks[1].column()), (4, 1));
+        assert_eq!(input.end().line(), 4);
+        assert_eq!(input.end().column(), 4);
+
+        let (x, _) = lines[0].chars().nth(2).unwrap();
+        assert_eq!((x.column(), x.as_str()), (3, "9"));
+
+        let sections = Span::new("a\nb\n  \n\nc\r\n\r\nd e\n")
+            .sections()
+            .collect::<Vec<_>>();
+        let sections = sections
+            .iter()
+            .map(|s| (s.as_str(), s.line()))
+            .collect::<Vec<_>>();
+        assert_eq!(sections, vec![("a\nb", 1), ("c", 5), ("d e", 7)]);
+    }
+
+    #[test]
+    fn test_span_errors() {
+        let error = Span::new("1 2\n3 x").lines().nth(1).unwrap();
+        let error = error
+            .split_whitespace()
+            .map(|n| n.parse::<u32>())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 3: expected u32, found \"x\""
+        );
+
+        let error = Span::new("abc").end().error("a guard");
+        assert_eq!(
+            error.to_string(),
+            "line 1, column 4: expected a guard, found nothing"
+        );
+
+        let error = Span::new("Game 1\nGame 2")
+            .strip_prefix("Card")
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 1, column 1: expected `Card`, found \"Game 1\"..."
+        );
+    }
+}
@@ -0,0 +1,379 @@
+//! Pair insertion, a string rewriting where every adjacent pair of symbols
+//! gets some symbols inserted between them at each step, all at once
+//!
+//! The strings grow exponentially, so only the number of times each pair
+//! appears is tracked. Every symbol except the last one starts exactly one
+//! pair, and the last symbol of the template never moves, which gives back
+//! the element counts
+//!
+//! ```text
+//! NNCB  with NN -> C, NC -> B, CB -> H
+//! NCNBCHB
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::Simulation;
+
+/// A template and its insertion rules, symbols are interned into small ids
+/// so that pairs are plain indices
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PairInsertion {
+    /// Every symbol of the template and the rules, sorted, a symbol's id is
+    /// its index
+    symbols: Vec<char>,
+    template: Vec<usize>,
+    /// What is inserted between the pair `a * symbols.len() + b`, `None`
+    /// when the pair has no rule and stays as it is
+    insertions: Vec<Option<Vec<usize>>>,
+    /// The pairs each pair turns into after one step, with repetitions
+    successors: Vec<Vec<usize>>,
+}
+
+/// How many times each symbol appears
+pub type ElementCounts = BTreeMap<char, u128>;
+
+impl PairInsertion {
+    /// `rules` insert any number of symbols, a later rule for the same pair
+    /// replaces the earlier one
+    pub fn new<S: AsRef<str>>(
+        template: &str,
+        rules: impl IntoIterator<Item = ((char, char), S)>,
+    ) -> Self {
+        let rules = rules
+            .into_iter()
+            .map(|(pair, inserted)| (pair, inserted.as_ref().chars().collect::<Vec<_>>()))
+            .collect::<HashMap<_, _>>();
+
+        let mut symbols = template.chars().collect::<Vec<_>>();
+        for (&(a, b), inserted) in &rules {
+            symbols.extend([a, b]);
+            symbols.extend(inserted);
+        }
+        symbols.sort_unstable();
+        symbols.dedup();
+        let id = |c: char| symbols.binary_search(&c).unwrap();
+
+        let n = symbols.len();
+        let mut insertions: Vec<Option<Vec<usize>>> = vec![None; n * n];
+        for ((a, b), inserted) in rules {
+            insertions[id(a) * n + id(b)] = Some(inserted.into_iter().map(id).collect());
+        }
+        let successors = insertions
+            .iter()
+            .enumerate()
+            .map(|(pair, inserted)| match inserted {
+                None => vec![pair],
+                Some(inserted) => std::iter::once(pair / n)
+                    .chain(inserted.iter().copied())
+                    .chain(std::iter::once(pair % n))
+                    .collect::<Vec<_>>()
+                    .windows(2)
+                    .map(|w| w[0] * n + w[1])
+                    .collect(),
+            })
+            .collect();
+        let template = template.chars().map(id).collect();
+        Self {
+            symbols,
+            template,
+            insertions,
+            successors,
+        }
+    }
+
+    fn pair(&self, a: usize, b: usize) -> usize {
+        a * self.symbols.len() + b
+    }
+
+    /// The pair counts of the template, to be stepped through
+    pub fn pair_counts(&self) -> PairCounts<'_> {
+        let mut pairs = vec![0; self.insertions.len()];
+        for w in self.template.windows(2) {
+            pairs[self.pair(w[0], w[1])] += 1;
+        }
+        PairCounts { rules: self, pairs }
+    }
+
+    /// The element counts after `steps` steps, one step at a time
+    ///
+    /// `None` when a count doesn't fit in a `u128`
+    pub fn element_counts(&self, steps: usize) -> Option<ElementCounts> {
+        let mut counts = self.pair_counts();
+        (counts.run(steps) == steps).then(|| counts.elements())
+    }
+
+    /// The element counts after `steps` steps, in O(log steps) products of
+    /// a matrix with one row and column per pair
+    ///
+    /// `None` when a count, or an intermediate product, doesn't fit in a `u128`
+    pub fn element_counts_by_squaring(&self, steps: u64) -> Option<ElementCounts> {
+        let n = self.symbols.len();
+        let mut matrix = Matrix::zero(n * n);
+        for (pair, successors) in self.successors.iter().enumerate() {
+            for &successor in successors {
+                matrix.0[pair][successor] += 1;
+            }
+        }
+        let matrix = matrix.power(steps)?;
+
+        let start = self.pair_counts().pairs;
+        let mut pairs = vec![0; n * n];
+        for (q, count) in pairs.iter_mut().enumerate() {
+            *count = start.iter().enumerate().try_fold(0u128, |sum, (p, &c)| {
+                sum.checked_add(c.checked_mul(matrix.0[p][q])?)
+            })?;
+        }
+        self.elements(&pairs)
+    }
+
+    /// Every symbol but the last starts exactly one pair, `None` on overflow
+    fn elements(&self, pairs: &[u128]) -> Option<ElementCounts> {
+        let n = self.symbols.len();
+        let mut counts = vec![0u128; n];
+        for (pair, &count) in pairs.iter().enumerate() {
+            counts[pair / n] = counts[pair / n].checked_add(count)?;
+        }
+        if let Some(&last) = self.template.last() {
+            counts[last] = counts[last].checked_add(1)?;
+        }
+        Some(
+            self.symbols
+                .iter()
+                .zip(counts)
+                .filter(|&(_, count)| count > 0)
+                .map(|(&symbol, count)| (symbol, count))
+                .collect(),
+        )
+    }
+
+    /// The first `len` symbols of the string after `steps` steps
+    ///
+    /// Only what is needed is expanded, each symbol takes at most `steps`
+    /// expansions so `steps` can be large as long as `len` is small
+    pub fn prefix(&self, steps: usize, len: usize) -> String {
+        let mut prefix = String::new();
+        let Some(&first) = self.template.first() else {
+            return prefix;
+        };
+        if len == 0 {
+            return prefix;
+        }
+        prefix.push(self.symbols[first]);
+        let mut pushed = 1;
+
+        // NOTE, Each entry expands to the symbols after its first one, the
+        // top of the stack is the leftmost part left to expand
+        let mut stack = self
+            .template
+            .windows(2)
+            .rev()
+            .map(|w| (w[0], w[1], steps))
+            .collect::<Vec<_>>();
+        while pushed < len {
+            let Some((a, b, steps)) = stack.pop() else {
+                break;
+            };
+            match &self.insertions[self.pair(a, b)] {
+                Some(inserted) if steps > 0 && !inserted.is_empty() => {
+                    let symbols = std::iter::once(a)
+                        .chain(inserted.iter().copied())
+                        .chain(std::iter::once(b))
+                        .collect::<Vec<_>>();
+                    stack.extend(symbols.windows(2).rev().map(|w| (w[0], w[1], steps - 1)));
+                }
+                _ => {
+                    prefix.push(self.symbols[b]);
+                    pushed += 1;
+                }
+            }
+        }
+        prefix
+    }
+}
+
+/// How many times each pair appears, see [`PairInsertion::pair_counts`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PairCounts<'a> {
+    rules: &'a PairInsertion,
+    pairs: Vec<u128>,
+}
+
+impl PairCounts<'_> {
+    pub fn elements(&self) -> ElementCounts {
+        // NOTE, Steps that would overflow an element count aren't taken
+        self.rules.elements(&self.pairs).unwrap()
+    }
+}
+
+impl Simulation for PairCounts<'_> {
+    type State = Vec<u128>;
+
+    /// `false` once a pair or element count would overflow, the counts are
+    /// then left as they were
+    fn step(&mut self) -> bool {
+        let mut pairs = vec![0u128; self.pairs.len()];
+        for (pair, &count) in self.pairs.iter().enumerate() {
+            if count == 0 {
+                continue;
+            }
+            for &successor in &self.rules.successors[pair] {
+                match pairs[successor].checked_add(count) {
+                    Some(sum) => pairs[successor] = sum,
+                    None => return false,
+                }
+            }
+        }
+        // NOTE, The element counts are sums of pair counts, which may still
+        // overflow even when every pair fits
+        if self.rules.elements(&pairs).is_none() {
+            return false;
+        }
+        self.pairs = pairs;
+        true
+    }
+
+    fn state(&self) -> Self::State {
+        self.pairs.clone()
+    }
+}
+
+/// A square matrix of counts
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Matrix(Vec<Vec<u128>>);
+
+impl Matrix {
+    fn zero(size: usize) -> Self {
+        Matrix(vec![vec![0; size]; size])
+    }
+
+    fn identity(size: usize) -> Self {
+        let mut identity = Self::zero(size);
+        for (i, row) in identity.0.iter_mut().enumerate() {
+            row[i] = 1;
+        }
+        identity
+    }
+
+    fn multiply(&self, other: &Matrix) -> Option<Matrix> {
+        let size = self.0.len();
+        let mut product = Self::zero(size);
+        for (i, row) in self.0.iter().enumerate() {
+            for (k, &a) in row.iter().enumerate() {
+                if a == 0 {
+                    continue;
+                }
+                for (j, &b) in other.0[k].iter().enumerate() {
+                    product.0[i][j] = product.0[i][j].checked_add(a.checked_mul(b)?)?;
+                }
+            }
+        }
+        Some(product)
+    }
+
+    /// By repeated squaring, `None` on overflow
+    fn power(&self, mut n: u64) -> Option<Matrix> {
+        let mut result = Self::identity(self.0.len());
+        let mut square = self.clone();
+        while n > 0 {
+            if n & 1 == 1 {
+                result = result.multiply(&square)?;
+            }
+            n >>= 1;
+            if n > 0 {
+                square = square.multiply(&square)?;
+            }
+        }
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The whole string, inserting into a copy of it
+    fn naive(template: &str, rules: &HashMap<(char, char), &str>, steps: usize) -> String {
+        let mut polymer = template.chars().collect::<Vec<_>>();
+        for _ in 0..steps {
+            let mut next = polymer[..1.min(polymer.len())].to_vec();
+            for w in polymer.windows(2) {
+                next.extend(rules.get(&(w[0], w[1])).unwrap_or(&"").chars());
+                next.push(w[1]);
+            }
+            polymer = next;
+        }
+        polymer.into_iter().collect()
+    }
+
+    fn count(polymer: &str) -> ElementCounts {
+        let mut counts = ElementCounts::new();
+        for c in polymer.chars() {
+            *counts.entry(c).or_default() += 1;
+        }
+        counts
+    }
+
+    #[test]
+    fn test_single_insertions() {
+        let rules = HashMap::from([
+            (('N', 'N'), "C"),
+            (('N', 'C'), "B"),
+            (('C', 'B'), "H"),
+            (('C', 'N'), "C"),
+            (('N', 'B'), "B"),
+            (('B', 'C'), "B"),
+            (('C', 'H'), "B"),
+            (('H', 'B'), "C"),
+        ]);
+        let insertion = PairInsertion::new("NNCB", rules.clone());
+        assert_eq!(insertion.prefix(1, 100), "NCNBCHB");
+        assert_eq!(insertion.prefix(2, 100), naive("NNCB", &rules, 2));
+        for steps in 0..8 {
+            let polymer = naive("NNCB", &rules, steps);
+            assert_eq!(insertion.prefix(steps, usize::MAX), polymer);
+            assert_eq!(insertion.element_counts(steps), Some(count(&polymer)));
+            assert_eq!(
+                insertion.element_counts_by_squaring(steps as u64),
+                Some(count(&polymer))
+            );
+        }
+    }
+
+    #[test]
+    fn test_longer_insertions() {
+        let rules = HashMap::from([(('A', 'B'), "BA"), (('B', 'A'), ""), (('A', 'A'), "CCC")]);
+        let insertion = PairInsertion::new("ABAB", rules.clone());
+        assert_eq!(insertion.prefix(1, 100), "ABABABAB");
+        for steps in 0..6 {
+            let polymer = naive("ABAB", &rules, steps);
+            assert_eq!(insertion.prefix(steps, 5), polymer[..5.min(polymer.len())]);
+            assert_eq!(insertion.element_counts(steps), Some(count(&polymer)));
+            assert_eq!(
+                insertion.element_counts_by_squaring(steps as u64),
+                Some(count(&polymer))
+            );
+        }
+
+        // NOTE, Far too long to build, but the start and the counts are known
+        assert_eq!(insertion.prefix(1_000_000, 6), "ABABAB");
+        let counts = insertion.element_counts_by_squaring(100).unwrap();
+        assert_eq!(insertion.element_counts(100), Some(counts.clone()));
+        assert_eq!(counts[&'A'] + counts[&'B'], 1 << 102);
+        assert_eq!(insertion.element_counts(200), None);
+        assert_eq!(insertion.element_counts_by_squaring(1 << 60), None);
+    }
+
+    #[test]
+    fn test_short_templates() {
+        let rules = [(('A', 'A'), "B")];
+        let insertion = PairInsertion::new("A", rules);
+        assert_eq!(insertion.prefix(10, 10), "A");
+        assert_eq!(insertion.element_counts(10), Some(count("A")));
+        assert_eq!(insertion.element_counts_by_squaring(10), Some(count("A")));
+
+        let insertion = PairInsertion::new("", rules);
+        assert_eq!(insertion.prefix(10, 10), "");
+        assert_eq!(insertion.element_counts(10), Some(ElementCounts::new()));
+    }
+}
@@ -0,0 +1,243 @@
+//! Step by step simulations, with helpers to run them and to skip ahead once
+//! they start repeating themselves
+
+use std::hash::Hash;
+
+/// Something that changes one step at a time
+pub trait Simulation {
+    /// Snapshot of everything that decides what the next steps look like, two
+    /// equal snapshots must lead to the same future
+    type State: Clone + Eq + Hash;
+
+    /// Advances by one step, `false` once the simulation has finished and
+    /// stepping does nothing anymore
+    fn step(&mut self) -> bool;
+
+    fn state(&self) -> Self::State;
+
+    /// Runs up to `steps` steps, returns how many were run before finishing
+    fn run(&mut self, steps: usize) -> usize {
+        (0..steps).take_while(|_| self.step()).count()
+    }
+
+    /// Steps until `done` is true, returns the number of steps that took or
+    /// `None` if the simulation finished first
+    fn run_until(&mut self, mut done: impl FnMut(&Self) -> bool) -> Option<usize> {
+        let mut steps = 0;
+        while !done(self) {
+            if !self.step() {
+                return None;
+            }
+            steps += 1;
+        }
+        Some(steps)
+    }
+
+    /// The state before each of the next `steps` steps and the one after them
+    fn history(&mut self, steps: usize) -> Vec<Self::State> {
+        let mut history = vec![self.state()];
+        for _ in 0..steps {
+            if !self.step() {
+                break;
+            }
+            history.push(self.state());
+        }
+        history
+    }
+
+    /// Finds where the states start repeating (Brent's algorithm), `None` if
+    /// the simulation finishes before that
+    ///
+    /// The simulation itself isn't advanced, the search runs on clones. Never
+    /// returns if the states neither repeat nor finish, see
+    /// [`Simulation::find_cycle_within`]
+    fn find_cycle(&self) -> Option<Cycle>
+    where
+        Self: Clone,
+    {
+        self.find_cycle_within(usize::MAX)
+    }
+
+    /// Same as [`Simulation::find_cycle`], giving up with `None` once the
+    /// search is more than `limit` steps ahead
+    fn find_cycle_within(&self, limit: usize) -> Option<Cycle>
+    where
+        Self: Clone,
+    {
+        // NOTE, The hare runs ahead and the tortoise teleports to it at every
+        // power of two, until the hare meets the tortoise again
+        let mut power = 1;
+        let mut length = 1;
+        let mut tortoise = self.state();
+        let mut hare = self.clone();
+        let mut ahead = 1;
+        if limit == 0 || !hare.step() {
+            return None;
+        }
+        while tortoise != hare.state() {
+            if power == length {
+                tortoise = hare.state();
+                power *= 2;
+                length = 0;
+            }
+            if ahead == limit || !hare.step() {
+                return None;
+            }
+            length += 1;
+            ahead += 1;
+        }
+
+        // NOTE, With the hare `length` steps ahead, both meet at the start of
+        // the cycle
+        let mut tortoise = self.clone();
+        let mut hare = self.clone();
+        hare.run(length);
+        let mut start = 0;
+        while tortoise.state() != hare.state() {
+            tortoise.step();
+            hare.step();
+            start += 1;
+        }
+        Some(Cycle { start, length })
+    }
+
+    /// Same as [`Simulation::run`], skipping whole cycles so that even
+    /// `1_000_000_000_000` steps are quick
+    ///
+    /// Returns the number of steps run or skipped, `steps` unless the
+    /// simulation finished. States that don't repeat within `steps` steps are
+    /// simply run
+    fn run_extrapolated(&mut self, steps: usize) -> usize
+    where
+        Self: Clone,
+    {
+        match self.find_cycle_within(steps) {
+            Some(cycle) => {
+                // NOTE, A repeating simulation never finishes, the skipped
+                // steps all count
+                self.run(cycle.equivalent_step(steps));
+                steps
+            }
+            None => self.run(steps),
+        }
+    }
+}
+
+/// From step `start` onwards, the state repeats every `length` steps
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// The earliest step with the same state as `step`
+    pub fn equivalent_step(&self, step: usize) -> usize {
+        if step < self.start {
+            step
+        } else {
+            self.start + (step - self.start) % self.length
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// x -> x * x + 1 (mod 1000), ends as soon as it reaches `stop`
+    #[derive(Clone)]
+    struct Squares {
+        x: u64,
+        stop: Option<u64>,
+    }
+
+    impl Simulation for Squares {
+        type State = u64;
+
+        fn step(&mut self) -> bool {
+            if Some(self.x) == self.stop {
+                return false;
+            }
+            self.x = (self.x * self.x + 1) % 1000;
+            true
+        }
+
+        fn state(&self) -> u64 {
+            self.x
+        }
+    }
+
+    #[test]
+    fn test_run() {
+        let squares = Squares { x: 3, stop: None };
+        assert_eq!(squares.clone().history(3), vec![3, 10, 101, 202]);
+
+        let mut run = squares.clone();
+        assert_eq!(run.run(2), 2);
+        assert_eq!(run.x, 101);
+        assert_eq!(run.run_until(|s| s.x == 202), Some(1));
+
+        let mut finishing = Squares {
+            x: 3,
+            stop: Some(101),
+        };
+        assert_eq!(finishing.run(10), 2);
+        assert_eq!(finishing.run_until(|s| s.x == 0), None);
+        assert_eq!(finishing.find_cycle(), None);
+    }
+
+    /// Counts up forever, never repeating
+    #[derive(Clone)]
+    struct Counter(u64);
+
+    impl Simulation for Counter {
+        type State = u64;
+
+        fn step(&mut self) -> bool {
+            self.0 += 1;
+            true
+        }
+
+        fn state(&self) -> u64 {
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_run_extrapolated_without_cycle() {
+        let mut counter = Counter(0);
+        assert_eq!(counter.find_cycle_within(1000), None);
+        assert_eq!(counter.run_extrapolated(10), 10);
+        assert_eq!(counter.0, 10);
+        assert_eq!(counter.run_extrapolated(0), 0);
+
+        let mut finishing = Squares {
+            x: 3,
+            stop: Some(101),
+        };
+        assert_eq!(finishing.run_extrapolated(10), 2);
+        assert_eq!(finishing.x, 101);
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        let squares = Squares { x: 3, stop: None };
+        let history = squares.clone().history(200);
+        let cycle = squares.find_cycle().unwrap();
+        let Cycle { start, length } = cycle;
+        assert!(length > 0);
+        assert_eq!(history[start], history[start + length]);
+        assert!((0..start).all(|s| !history[s + 1..].contains(&history[s])));
+        assert!((start + 1..start + length).all(|s| history[s] != history[start]));
+
+        for steps in [0, 5, 150, 1_000_000_000_000] {
+            let mut extrapolated = squares.clone();
+            assert_eq!(extrapolated.run_extrapolated(steps), steps);
+            assert_eq!(extrapolated.x, history[cycle.equivalent_step(steps)]);
+        }
+        let mut naive = squares.clone();
+        naive.run(150);
+        assert_eq!(naive.x, history[cycle.equivalent_step(150)]);
+    }
+}
@@ -0,0 +1,350 @@
+use std::{error::Error, fmt, sync::LazyLock};
+
+use crate::{Answer, AocError};
+
+/// A single part of an Advent of Code puzzle
+///
+/// The runner no longer infers the day and part from an index into a list,
+/// every solution states exactly which puzzle it answers
+pub trait Solution: Send + Sync {
+    fn year(&self) -> u32;
+    fn day(&self) -> u32;
+    fn part(&self) -> u32;
+    fn solve(&self, input: &str) -> Result<Answer, AocError>;
+
+    /// Name of an alternative implementation, `None` for the one the runner uses
+    fn variant(&self) -> Option<&str> {
+        None
+    }
+}
+
+/// Adapts a plain `dayN_partM_solution` function into a [`Solution`]
+///
+/// Usually created by the [`crate::aoc`] attribute rather than by hand
+#[derive(Clone, Copy)]
+pub struct SolutionFn {
+    year: u32,
+    day: u32,
+    part: u32,
+    variant: Option<&'static str>,
+    solver: fn(&str) -> Result<Answer, AocError>,
+}
+
+impl SolutionFn {
+    pub const fn new(
+        year: u32,
+        day: u32,
+        part: u32,
+        solver: fn(&str) -> Result<Answer, AocError>,
+    ) -> Self {
+        Self {
+            year,
+            day,
+            part,
+            variant: None,
+            solver,
+        }
+    }
+
+    pub const fn with_variant(self, variant: &'static str) -> Self {
+        Self {
+            variant: Some(variant),
+            ..self
+        }
+    }
+}
+
+inventory::collect!(SolutionFn);
+
+impl Solution for SolutionFn {
+    fn year(&self) -> u32 {
+        self.year
+    }
+
+    fn day(&self) -> u32 {
+        self.day
+    }
+
+    fn part(&self) -> u32 {
+        self.part
+    }
+
+    fn solve(&self, input: &str) -> Result<Answer, AocError> {
+        (self.solver)(input)
+    }
+
+    fn variant(&self) -> Option<&str> {
+        self.variant
+    }
+}
+
+/// Collection of every [`Solution`] known to the runner
+///
+/// Solutions are kept ordered by (year, day, part) irrespective of the order
+/// in which they were registered
+///
+/// Variants (see [`Solution::variant`]) are kept aside, only
+/// [`Registry::implementations`] and [`Registry::cross_check`] see them, and
+/// the runner's verify checks them too
+#[derive(Default)]
+pub struct Registry {
+    solutions: Vec<Box<dyn Solution>>,
+    variants: Vec<Box<dyn Solution>>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds a registry from every function annotated with `#[aoc(...)]`
+    /// in the crates linked into the current binary
+    pub fn collect() -> Self {
+        let mut registry = Self::new();
+        for solution in inventory::iter::<SolutionFn> {
+            registry.register(*solution);
+        }
+        registry
+    }
+
+    /// Panics if a solution for the same year, day, part and variant is
+    /// already registered
+    pub fn register(&mut self, solution: impl Solution + 'static) {
+        let key = (
+            solution.year(),
+            solution.day(),
+            solution.part(),
+            solution.variant().map(str::to_owned),
+        );
+        let solutions = match key.3 {
+            Some(_) => &mut self.variants,
+            None => &mut self.solutions,
+        };
+        match solutions.binary_search_by(|s| {
+            (s.year(), s.day(), s.part(), s.variant()).cmp(&(key.0, key.1, key.2, key.3.as_deref()))
+        }) {
+            Ok(_) => match &key.3 {
+                Some(variant) => panic!(
+                    "Year {} Day {} Part {} variant `{}` is already registered",
+                    key.0, key.1, key.2, variant
+                ),
+                None => panic!(
+                    "Year {} Day {} Part {} is already registered",
+                    key.0, key.1, key.2
+                ),
+            },
+            Err(index) => solutions.insert(index, Box::new(solution)),
+        }
+    }
+
+    pub fn get(&self, year: u32, day: u32, part: u32) -> Option<&dyn Solution> {
+        self.iter()
+            .find(|s| s.year() == year && s.day() == day && s.part() == part)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Solution> {
+        self.solutions.iter().map(|s| s.as_ref())
+    }
+
+    /// Every implementation of a part, the default one first and then the
+    /// variants ordered by name
+    pub fn implementations(&self, year: u32, day: u32, part: u32) -> Vec<&dyn Solution> {
+        self.get(year, day, part)
+            .into_iter()
+            .chain(
+                self.variants
+                    .iter()
+                    .map(|s| s.as_ref())
+                    .filter(|s| (s.year(), s.day(), s.part()) == (year, day, part)),
+            )
+            .collect()
+    }
+
+    /// Solves `input` with every implementation of a part and compares each
+    /// outcome with the first one
+    ///
+    /// Implementations agree when they give the same answer or when they
+    /// all fail, whatever the error. A part with a single implementation
+    /// trivially agrees with itself
+    pub fn cross_check(
+        &self,
+        year: u32,
+        day: u32,
+        part: u32,
+        input: &str,
+    ) -> Result<(), Box<Mismatch>> {
+        let mut implementations = self.implementations(year, day, part).into_iter();
+        let Some(first) = implementations.next() else {
+            return Ok(());
+        };
+        let outcome = |solution: &dyn Solution| Outcome {
+            variant: solution.variant().unwrap_or("default").to_owned(),
+            answer: solution.solve(input).map_err(|e| e.to_string()),
+        };
+        let expected = outcome(first);
+        for solution in implementations {
+            let actual = outcome(solution);
+            let agree = match (&expected.answer, &actual.answer) {
+                (Ok(a), Ok(b)) => a == b,
+                (Err(_), Err(_)) => true,
+                _ => false,
+            };
+            if !agree {
+                return Err(Box::new(Mismatch {
+                    year,
+                    day,
+                    part,
+                    expected,
+                    actual,
+                }));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.solutions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.solutions.is_empty()
+    }
+}
+
+/// What one implementation made of an input, errors are kept as their
+/// message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    pub variant: String,
+    pub answer: Result<Answer, String>,
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.answer {
+            Ok(answer) => write!(f, "`{}` answers {}", self.variant, answer),
+            Err(e) => write!(f, "`{}` fails, {}", self.variant, e),
+        }
+    }
+}
+
+/// Two implementations of the same part disagree, see [`Registry::cross_check`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    pub year: u32,
+    pub day: u32,
+    pub part: u32,
+    pub expected: Outcome,
+    pub actual: Outcome,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Year {} Day {} Part {}: {} but {}",
+            self.year, self.day, self.part, self.expected, self.actual
+        )
+    }
+}
+
+impl Error for Mismatch {}
+
+/// Panics with the first [`Mismatch`] between the implementations of a part
+/// of `day`, for property tests that feed every implementation the same
+/// generated input
+pub fn assert_implementations_agree(year: u32, day: u32, input: &str) {
+    static REGISTRY: LazyLock<Registry> = LazyLock::new(Registry::collect);
+    for part in [1, 2] {
+        if let Err(mismatch) = REGISTRY.cross_check(year, day, part, input) {
+            panic!("{}", mismatch);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn answer(input: &str) -> Result<Answer, AocError> {
+        Ok(input.len().to_string())
+    }
+
+    #[test]
+    fn test_registry_is_ordered() {
+        let mut registry = Registry::new();
+        registry.register(SolutionFn::new(2024, 1, 2, answer));
+        registry.register(SolutionFn::new(2021, 14, 1, answer));
+        registry.register(SolutionFn::new(2024, 1, 1, answer));
+        registry.register(SolutionFn::new(2021, 2, 1, answer));
+
+        let keys = registry
+            .iter()
+            .map(|s| (s.year(), s.day(), s.part()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            keys,
+            vec![(2021, 2, 1), (2021, 14, 1), (2024, 1, 1), (2024, 1, 2)]
+        );
+
+        let solution = registry.get(2021, 14, 1).unwrap();
+        assert_eq!(solution.solve("NNCB").unwrap(), "4");
+        assert!(registry.get(2021, 14, 2).is_none());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_registry_rejects_duplicates() {
+        let mut registry = Registry::new();
+        registry.register(SolutionFn::new(2024, 6, 2, answer));
+        registry.register(SolutionFn::new(2024, 6, 2, answer));
+    }
+
+    fn fail(input: &str) -> Result<Answer, AocError> {
+        match input {
+            "" => Err(AocError::MissingInput("nothing.txt".into())),
+            _ => answer(input),
+        }
+    }
+
+    fn off_by_one(input: &str) -> Result<Answer, AocError> {
+        Ok((input.len() + 1).to_string())
+    }
+
+    #[test]
+    fn test_registry_variants() {
+        let mut registry = Registry::new();
+        registry.register(SolutionFn::new(2021, 6, 1, off_by_one).with_variant("wrong"));
+        registry.register(SolutionFn::new(2021, 6, 1, fail).with_variant("fast"));
+        registry.register(SolutionFn::new(2021, 6, 1, answer));
+        registry.register(SolutionFn::new(2021, 6, 2, answer));
+
+        assert_eq!(registry.len(), 2);
+        assert_eq!(registry.get(2021, 6, 1).unwrap().variant(), None);
+        let variants = registry
+            .implementations(2021, 6, 1)
+            .iter()
+            .map(|s| s.variant())
+            .collect::<Vec<_>>();
+        assert_eq!(variants, vec![None, Some("fast"), Some("wrong")]);
+
+        assert_eq!(registry.cross_check(2021, 6, 2, "abc"), Ok(()));
+        assert_eq!(registry.cross_check(2021, 7, 1, "abc"), Ok(()));
+        let mismatch = registry.cross_check(2021, 6, 1, "abc").unwrap_err();
+        assert_eq!(
+            mismatch.to_string(),
+            "Year 2021 Day 6 Part 1: `default` answers 3 but `wrong` answers 4"
+        );
+        let mismatch = registry.cross_check(2021, 6, 1, "").unwrap_err();
+        assert_eq!(mismatch.actual.variant, "fast");
+        assert!(mismatch.actual.answer.is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_registry_rejects_duplicate_variants() {
+        let mut registry = Registry::new();
+        registry.register(SolutionFn::new(2024, 6, 2, answer).with_variant("fast"));
+        registry.register(SolutionFn::new(2024, 6, 2, answer).with_variant("fast"));
+    }
+}
@@ -0,0 +1,49 @@
+use std::{
+    cell::Cell,
+    time::{Duration, Instant},
+};
+
+thread_local! {
+    static PARSE_TIME: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+}
+
+/// Runs the parsing step of a solution and records how long it took
+///
+/// Solutions only return their answer, wrapping the parser lets the runner
+/// report the parse and solve phases separately
+///
+/// ```ignore
+/// let parsed_data = timing::parse(|| parse_input(input));
+/// ```
+pub fn parse<T>(parser: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let parsed = parser();
+    let elapsed = start.elapsed();
+    PARSE_TIME.with(|t| t.set(t.get() + elapsed));
+    parsed
+}
+
+/// Parse time accumulated on the current thread since the last call
+pub fn take_parse_time() -> Duration {
+    PARSE_TIME.with(|t| t.replace(Duration::ZERO))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_time_is_accumulated_and_reset() {
+        take_parse_time();
+        let parsed = parse(|| {
+            thread::sleep(Duration::from_millis(2));
+            "1,2,3".split(',').count()
+        });
+        parse(|| thread::sleep(Duration::from_millis(2)));
+        assert_eq!(parsed, 3);
+        assert!(take_parse_time() >= Duration::from_millis(4));
+        assert_eq!(take_parse_time(), Duration::ZERO);
+    }
+}
@@ -0,0 +1,222 @@
+//! Frame by frame rendering of simulations, played by the runner's
+//! `--visualize` mode
+//!
+//! A puzzle implements [`Render`] next to [`Simulation`] and registers a
+//! builder with `#[visualize(year = .., day = ..)]`. Nothing here knows about
+//! terminals, a [`Frame`] is only a grid of coloured characters
+
+use std::fmt;
+
+use crate::{AocError, Grid, Simulation};
+
+/// The 8 basic ANSI colours, the terminal decides how they actually look,
+/// or an exact colour for terminals with true colour support
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    Rgb(u8, u8, u8),
+}
+
+/// One character of a frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cell {
+    pub symbol: char,
+    /// `None` keeps the terminal's default colour
+    pub color: Option<Color>,
+    pub bold: bool,
+}
+
+impl Cell {
+    pub const fn new(symbol: char) -> Self {
+        Self {
+            symbol,
+            color: None,
+            bold: false,
+        }
+    }
+
+    pub const fn colored(symbol: char, color: Color) -> Self {
+        Self {
+            symbol,
+            color: Some(color),
+            bold: false,
+        }
+    }
+
+    pub const fn bold(self) -> Self {
+        Self { bold: true, ..self }
+    }
+}
+
+impl From<char> for Cell {
+    fn from(symbol: char) -> Self {
+        Cell::new(symbol)
+    }
+}
+
+/// Only the symbol, colours are up to whoever draws the frame
+impl fmt::Display for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.symbol)
+    }
+}
+
+/// Everything shown for one step
+pub type Frame = Grid<Cell>;
+
+/// Something that can be drawn as a [`Frame`]
+pub trait Render {
+    fn render(&self) -> Frame;
+
+    /// A short line shown above the frame, e.g. a counter the puzzle asks for
+    fn caption(&self) -> String {
+        String::new()
+    }
+}
+
+/// A [`Simulation`] that can be rendered, without its associated state type
+/// so that the runner can hold any of them
+pub trait Animation {
+    /// See [`Simulation::step`]
+    fn advance(&mut self) -> bool;
+
+    fn frame(&self) -> Frame;
+
+    fn status(&self) -> String;
+}
+
+impl<S: Simulation + Render> Animation for S {
+    fn advance(&mut self) -> bool {
+        self.step()
+    }
+
+    fn frame(&self) -> Frame {
+        self.render()
+    }
+
+    fn status(&self) -> String {
+        self.caption()
+    }
+}
+
+/// Builds the animation of a puzzle from its input
+///
+/// Usually created by the [`crate::visualize`] attribute rather than by hand
+#[derive(Clone, Copy)]
+pub struct Visualization {
+    year: u32,
+    day: u32,
+    builder: fn(&str) -> Result<Box<dyn Animation>, AocError>,
+}
+
+impl Visualization {
+    pub const fn new(
+        year: u32,
+        day: u32,
+        builder: fn(&str) -> Result<Box<dyn Animation>, AocError>,
+    ) -> Self {
+        Self { year, day, builder }
+    }
+
+    pub fn year(&self) -> u32 {
+        self.year
+    }
+
+    pub fn day(&self) -> u32 {
+        self.day
+    }
+
+    pub fn build(&self, input: &str) -> Result<Box<dyn Animation>, AocError> {
+        (self.builder)(input)
+    }
+}
+
+inventory::collect!(Visualization);
+
+/// Every visualization linked into the current binary, ordered by year and day
+pub fn visualizations() -> Vec<&'static Visualization> {
+    let mut visualizations = inventory::iter::<Visualization>
+        .into_iter()
+        .collect::<Vec<_>>();
+    visualizations.sort_by_key(|v| (v.year, v.day));
+    visualizations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A dot bouncing between the two ends of a row
+    struct Bounce {
+        x: usize,
+        forward: bool,
+        bounces: u32,
+    }
+
+    impl Simulation for Bounce {
+        type State = (usize, bool);
+
+        fn step(&mut self) -> bool {
+            if self.bounces == 2 {
+                return false;
+            }
+            match (self.x, self.forward) {
+                (3, true) | (0, false) => {
+                    self.forward = !self.forward;
+                    self.bounces += 1;
+                }
+                (_, true) => self.x += 1,
+                (_, false) => self.x -= 1,
+            }
+            true
+        }
+
+        fn state(&self) -> Self::State {
+            (self.x, self.forward)
+        }
+    }
+
+    impl Render for Bounce {
+        fn render(&self) -> Frame {
+            let mut frame = Grid::new(4, 1, Cell::new('.'));
+            frame[crate::Point::new(self.x, 0)] = Cell::colored('o', Color::Red).bold();
+            frame
+        }
+
+        fn caption(&self) -> String {
+            format!("{} bounces", self.bounces)
+        }
+    }
+
+    fn build(_input: &str) -> Result<Box<dyn Animation>, AocError> {
+        Ok(Box::new(Bounce {
+            x: 0,
+            forward: true,
+            bounces: 0,
+        }))
+    }
+
+    #[test]
+    fn test_animation() {
+        let visualization = Visualization::new(2021, 1, build);
+        let mut animation = visualization.build("").unwrap();
+        let mut frames = vec![animation.frame().to_string()];
+        while animation.advance() {
+            frames.push(animation.frame().to_string());
+        }
+        assert_eq!(frames.len(), 9);
+        assert_eq!(frames[..5], ["o...", ".o..", "..o.", "...o", "...o"]);
+        assert_eq!(frames.last().unwrap(), "o...");
+        assert_eq!(animation.status(), "2 bounces");
+
+        let cell = animation.frame()[crate::Point::new(0, 0)];
+        assert_eq!((cell.color, cell.bold), (Some(Color::Red), true));
+    }
+}
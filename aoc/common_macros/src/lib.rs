@@ -0,0 +1,161 @@
+use std::ops::RangeInclusive;
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{parse::Parser, parse_macro_input, ItemFn, LitInt, LitStr};
+
+/// Registers a `dayN_partM_solution` function with the runner
+///
+/// ```ignore
+/// #[aoc(year = 2024, day = 6, part = 2)]
+/// pub fn day6_part2_solution(input: &str) -> String {
+///     // ...
+/// }
+/// ```
+///
+/// The function is left untouched, a `common::SolutionFn` pointing at it is
+/// submitted to the inventory that `common::Registry::collect` iterates
+///
+/// An optional `variant = "name"` registers an alternative implementation of
+/// a part that already has one, the runner ignores it but
+/// `common::Registry::cross_check` compares it with the default one
+#[proc_macro_attribute]
+pub fn aoc(args: TokenStream, item: TokenStream) -> TokenStream {
+    let keys = [("year", 2015..=9999), ("day", 1..=25), ("part", 1..=2)];
+    let mut variant = None;
+    let values = match parse_arguments("aoc", args, keys, Some(("variant", &mut variant))) {
+        Ok(values) => values,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let function = parse_macro_input!(item as ItemFn);
+
+    let [year, day, part] = values;
+    let ident = &function.sig.ident;
+    let solution = match variant {
+        Some(variant) => quote! {
+            ::common::SolutionFn::new(#year, #day, #part, #ident).with_variant(#variant)
+        },
+        None => quote! { ::common::SolutionFn::new(#year, #day, #part, #ident) },
+    };
+    quote! {
+        #function
+
+        ::common::inventory::submit! {
+            #solution
+        }
+    }
+    .into()
+}
+
+/// Registers the builder of a `--visualize` animation with the runner
+///
+/// ```ignore
+/// #[visualize(year = 2024, day = 6)]
+/// fn guard_walk(input: &str) -> Result<GuardWalk, AocError> {
+///     // ...
+/// }
+/// ```
+///
+/// The returned type must implement `common::visualize::Animation`, usually
+/// through `Simulation` and `Render`. It is boxed by a `common::visualize::Visualization`
+/// submitted to the inventory
+#[proc_macro_attribute]
+pub fn visualize(args: TokenStream, item: TokenStream) -> TokenStream {
+    let keys = [("year", 2015..=9999), ("day", 1..=25)];
+    let values = match parse_arguments("visualize", args, keys, None) {
+        Ok(values) => values,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let function = parse_macro_input!(item as ItemFn);
+
+    let [year, day] = values;
+    let ident = &function.sig.ident;
+    quote! {
+        #function
+
+        ::common::inventory::submit! {
+            ::common::visualize::Visualization::new(#year, #day, |input| {
+                #ident(input).map(|animation| {
+                    ::std::boxed::Box::new(animation)
+                        as ::std::boxed::Box<dyn ::common::visualize::Animation>
+                })
+            })
+        }
+    }
+    .into()
+}
+
+/// Parses `key = value` arguments, every key is required and its value must
+/// be in the given range
+///
+/// `optional` is a key whose value is a non empty string, stored in the
+/// `Option` when given
+fn parse_arguments<const N: usize>(
+    attribute: &str,
+    args: TokenStream,
+    keys: [(&str, RangeInclusive<u32>); N],
+    mut optional: Option<(&str, &mut Option<String>)>,
+) -> syn::Result<[u32; N]> {
+    let mut values = [None; N];
+    let parser = syn::meta::parser(|meta| {
+        if let Some((key, value)) = optional.as_mut() {
+            if meta.path.is_ident(key) {
+                let literal: LitStr = meta.value()?.parse()?;
+                if literal.value().is_empty() {
+                    return Err(syn::Error::new(literal.span(), "expected a non empty name"));
+                }
+                **value = Some(literal.value());
+                return Ok(());
+            }
+        }
+        let Some(index) = keys.iter().position(|(key, _)| meta.path.is_ident(key)) else {
+            let names = keys
+                .iter()
+                .map(|k| k.0)
+                .chain(optional.as_ref().map(|o| o.0));
+            return Err(meta.error(format!("expected {}", quoted(names, "or"))));
+        };
+
+        let range = &keys[index].1;
+        let literal: LitInt = meta.value()?.parse()?;
+        let value = literal.base10_parse::<u32>()?;
+        if !range.contains(&value) {
+            return Err(syn::Error::new(
+                literal.span(),
+                format!(
+                    "expected a value between {} and {}",
+                    range.start(),
+                    range.end()
+                ),
+            ));
+        }
+        values[index] = Some(value);
+        Ok(())
+    });
+    parser.parse(args)?;
+
+    let missing = keys
+        .iter()
+        .zip(values)
+        .filter(|(_, value)| value.is_none())
+        .map(|((key, _), _)| *key);
+    let missing = quoted(missing, "and");
+    if !missing.is_empty() {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            format!("missing {} in #[{}(...)]", missing, attribute),
+        ));
+    }
+    Ok(values.map(Option::unwrap))
+}
+
+/// "`a`, `b` or `c`", with `conjunction` instead of `or`
+fn quoted<'a>(names: impl Iterator<Item = &'a str>, conjunction: &str) -> String {
+    let names = names.map(|name| format!("`{name}`")).collect::<Vec<_>>();
+    match names.split_last() {
+        Some((last, [])) => last.clone(),
+        Some((last, rest)) => format!("{} {} {}", rest.join(", "), conjunction, last),
+        None => String::new(),
+    }
+}
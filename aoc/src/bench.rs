@@ -0,0 +1,320 @@
+use std::{
+    collections::HashMap,
+    fmt::Write as _,
+    fs,
+    path::Path,
+    process::ExitCode,
+    time::{Duration, Instant},
+};
+
+use common::{timing, AocError, Solution};
+use serde::{Deserialize, Serialize};
+
+use crate::{cli::BenchArgs, input::Inputs, parallel};
+
+/// Min, median and max over all runs, in nanoseconds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Stats {
+    pub min: u64,
+    pub median: u64,
+    pub max: u64,
+}
+
+impl Stats {
+    fn new(mut samples: Vec<Duration>) -> Self {
+        assert!(!samples.is_empty());
+        samples.sort();
+        let nanos = |d: Duration| d.as_nanos().try_into().unwrap_or(u64::MAX);
+        Self {
+            min: nanos(samples[0]),
+            median: nanos(samples[samples.len() / 2]),
+            max: nanos(samples[samples.len() - 1]),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BenchResult {
+    pub year: u32,
+    pub day: u32,
+    pub part: u32,
+    /// See [`Solution::variant`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variant: Option<String>,
+    pub runs: u32,
+    pub parse: Stats,
+    pub solve: Stats,
+    pub total: Stats,
+}
+
+/// Times `solution` over `runs` repetitions
+///
+/// The parse phase is whatever the solution wrapped in [`timing::parse`],
+/// the solve phase is the remainder of the total time. Stops at the first
+/// error, the answer is the same on every run
+pub fn bench_solution(
+    solution: &dyn Solution,
+    input: &str,
+    runs: u32,
+) -> Result<BenchResult, AocError> {
+    let mut parse = Vec::new();
+    let mut solve = Vec::new();
+    let mut total = Vec::new();
+    for _ in 0..runs {
+        timing::take_parse_time();
+        let start = Instant::now();
+        solution.solve(input)?;
+        let elapsed = start.elapsed();
+        let parse_time = timing::take_parse_time();
+
+        parse.push(parse_time);
+        solve.push(elapsed.saturating_sub(parse_time));
+        total.push(elapsed);
+    }
+
+    Ok(BenchResult {
+        year: solution.year(),
+        day: solution.day(),
+        part: solution.part(),
+        variant: solution.variant().map(str::to_owned),
+        runs,
+        parse: Stats::new(parse),
+        solve: Stats::new(solve),
+        total: Stats::new(total),
+    })
+}
+
+fn to_csv(results: &[BenchResult]) -> String {
+    let mut csv = String::from("year,day,part,variant,runs");
+    for phase in ["parse", "solve", "total"] {
+        for stat in ["min", "median", "max"] {
+            write!(csv, ",{}_{}_ns", phase, stat).unwrap();
+        }
+    }
+    csv.push('\n');
+
+    for r in results {
+        let variant = r.variant.as_deref().unwrap_or_default();
+        write!(
+            csv,
+            "{},{},{},{},{}",
+            r.year, r.day, r.part, variant, r.runs
+        )
+        .unwrap();
+        for stats in [r.parse, r.solve, r.total] {
+            write!(csv, ",{},{},{}", stats.min, stats.median, stats.max).unwrap();
+        }
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Median total times of `results` that are slower than `baseline` by more
+/// than `threshold` percent, as (result, baseline median) pairs
+fn regressions<'a>(
+    results: &'a [BenchResult],
+    baseline: &[BenchResult],
+    threshold: f64,
+) -> Vec<(&'a BenchResult, u64)> {
+    let baseline = baseline
+        .iter()
+        .map(|b| {
+            (
+                (b.year, b.day, b.part, b.variant.as_deref()),
+                b.total.median,
+            )
+        })
+        .collect::<HashMap<_, _>>();
+
+    results
+        .iter()
+        .filter_map(|r| {
+            let before = *baseline.get(&(r.year, r.day, r.part, r.variant.as_deref()))?;
+            let allowed = before as f64 * (1.0 + threshold / 100.0);
+            (r.total.median as f64 > allowed).then_some((r, before))
+        })
+        .collect()
+}
+
+fn format_stats(stats: &Stats) -> String {
+    format!(
+        "{:?} / {:?} / {:?}",
+        Duration::from_nanos(stats.min),
+        Duration::from_nanos(stats.median),
+        Duration::from_nanos(stats.max)
+    )
+}
+
+/// Benches every solution on a solution thread of its own, a panic is
+/// reported like an error and only fails that solution
+pub fn bench(args: &BenchArgs, inputs: &Inputs, solutions: &[&dyn Solution]) -> ExitCode {
+    let baseline = match &args.baseline {
+        Some(path) => match read_report(path) {
+            Ok(baseline) => Some(baseline),
+            Err(e) => {
+                eprintln!("Failed to read the baseline {}: {}", path.display(), e);
+                return ExitCode::FAILURE;
+            }
+        },
+        None => None,
+    };
+
+    let mut exit_code = ExitCode::SUCCESS;
+    let mut results = Vec::new();
+    println!("Year \tDay \tPart \tParse (min / median / max) \tSolve (min / median / max)");
+    for solution in solutions {
+        let part = crate::part_label(*solution);
+        let result = inputs.read(*solution).map_err(|e| format!("Error: {}", e));
+        let result = result.and_then(|input| {
+            match parallel::run_isolated(|| bench_solution(*solution, &input, args.runs)) {
+                Ok(result) => result.map_err(|e| format!("Error: {}", e)),
+                Err(message) => Err(format!("Panicked: {}", message)),
+            }
+        });
+        let result = match result {
+            Ok(result) => result,
+            Err(e) => {
+                eprintln!(
+                    "{} \t{} \t{} \t{}",
+                    solution.year(),
+                    solution.day(),
+                    part,
+                    e
+                );
+                exit_code = ExitCode::FAILURE;
+                continue;
+            }
+        };
+
+        println!(
+            "{} \t{} \t{} \t{} \t{}",
+            result.year,
+            result.day,
+            part,
+            format_stats(&result.parse),
+            format_stats(&result.solve)
+        );
+        results.push(result);
+    }
+
+    if let Some(path) = &args.json {
+        let json = serde_json::to_string_pretty(&results).unwrap();
+        if let Err(e) = fs::write(path, json) {
+            eprintln!("Failed to write {}: {}", path.display(), e);
+            exit_code = ExitCode::FAILURE;
+        }
+    }
+    if let Some(path) = &args.csv {
+        if let Err(e) = fs::write(path, to_csv(&results)) {
+            eprintln!("Failed to write {}: {}", path.display(), e);
+            exit_code = ExitCode::FAILURE;
+        }
+    }
+
+    if let Some(baseline) = baseline {
+        let regressed = regressions(&results, &baseline, args.threshold);
+        for (r, before) in &regressed {
+            println!(
+                "REGRESSION {} \t{} \t{} \tmedian {:?} -> {:?}",
+                r.year,
+                r.day,
+                r.part,
+                Duration::from_nanos(*before),
+                Duration::from_nanos(r.total.median)
+            );
+        }
+        println!(
+            "{} of {} solutions slower than the baseline by more than {}%",
+            regressed.len(),
+            results.len(),
+            args.threshold
+        );
+        if !regressed.is_empty() {
+            exit_code = ExitCode::FAILURE;
+        }
+    }
+    exit_code
+}
+
+fn read_report(path: &Path) -> Result<Vec<BenchResult>, String> {
+    let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&content).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use common::{Answer, SolutionFn, Span};
+
+    use super::*;
+
+    fn answer(input: &str) -> Result<Answer, AocError> {
+        let numbers = timing::parse(|| {
+            Span::new(input)
+                .split(",")
+                .map(|x| x.parse::<u32>())
+                .collect::<Result<Vec<u32>, _>>()
+        })?;
+        Ok(numbers.iter().sum::<u32>().to_string())
+    }
+
+    fn result(day: u32, median: u64) -> BenchResult {
+        let stats = Stats {
+            min: median,
+            median,
+            max: median,
+        };
+        BenchResult {
+            year: 2021,
+            day,
+            part: 1,
+            variant: None,
+            runs: 1,
+            parse: stats,
+            solve: stats,
+            total: stats,
+        }
+    }
+
+    #[test]
+    fn test_bench_solution() {
+        let solution = SolutionFn::new(2021, 7, 1, answer);
+        let result = bench_solution(&solution, "16,1,2,0,4,2,7,1,2,14", 5).unwrap();
+        assert_eq!((result.year, result.day, result.part), (2021, 7, 1));
+        assert_eq!(result.runs, 5);
+        assert!(result.parse.min <= result.parse.median && result.parse.median <= result.parse.max);
+        assert!(result.parse.min > 0);
+        assert!(result.total.max >= result.parse.max);
+
+        assert!(matches!(
+            bench_solution(&solution, "16,1,x", 5),
+            Err(AocError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn test_bench_report() {
+        let results = vec![result(1, 100), result(2, 200)];
+
+        let json = serde_json::to_string(&results).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Vec<BenchResult>>(&json).unwrap(),
+            results
+        );
+
+        let csv = to_csv(&results);
+        let lines = csv.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("year,day,part,variant,runs,parse_min_ns"));
+        assert_eq!(lines[1], "2021,1,1,,1,100,100,100,100,100,100,100,100,100");
+
+        let mut variant = result(1, 1);
+        variant.variant = Some("fast".to_string());
+        assert!(to_csv(&[variant.clone()]).contains("\n2021,1,1,fast,1,"));
+
+        let baseline = vec![result(1, 100), result(2, 100), variant];
+        let regressed = regressions(&results, &baseline, 10.0);
+        assert_eq!(regressed.len(), 1);
+        assert_eq!((regressed[0].0.day, regressed[0].1), (2, 100));
+        assert!(regressions(&results, &baseline, 150.0).is_empty());
+    }
+}
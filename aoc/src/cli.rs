@@ -0,0 +1,302 @@
+use std::{
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use clap::{Args, Parser, Subcommand};
+use common::Solution;
+
+/// Run Advent of Code solutions
+///
+/// Without any arguments every registered solution is run against its puzzle
+/// input in `<year>/inputs`
+#[derive(Debug, Parser)]
+#[command(name = "aoc", version, about)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Only run solutions for this year
+    #[arg(short, long, global = true)]
+    pub year: Option<u32>,
+
+    /// Only run solutions for this day
+    #[arg(short, long, global = true, value_parser = clap::value_parser!(u32).range(1..=25))]
+    pub day: Option<u32>,
+
+    /// Only run this part
+    #[arg(short, long, global = true, value_parser = clap::value_parser!(u32).range(1..=2))]
+    pub part: Option<u32>,
+
+    /// Read the input from PATH instead of `<year>/inputs`, `-` reads from stdin
+    #[arg(
+        short,
+        long,
+        global = true,
+        value_name = "PATH",
+        conflicts_with = "example"
+    )]
+    pub input: Option<PathBuf>,
+
+    /// Use the stored example `<year>/examples/dayN_example.txt`,
+    /// K > 1 picks `dayN_example_K.txt` for days with several examples
+    #[arg(
+        short,
+        long,
+        global = true,
+        value_name = "K",
+        num_args = 0..=1,
+        default_missing_value = "1",
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    pub example: Option<u32>,
+
+    /// Number of solutions solved in parallel, defaults to the number of CPUs
+    ///
+    /// bench always runs one solution at a time
+    #[arg(short, long, global = true)]
+    pub jobs: Option<NonZeroUsize>,
+
+    /// Give up on a solution that takes longer than SECONDS
+    ///
+    /// bench ignores it, every run goes to the end to be timed
+    #[arg(long, global = true, value_name = "SECONDS", value_parser = parse_timeout)]
+    pub timeout: Option<Duration>,
+
+    /// Print the notes solutions leave about their input before their answer,
+    /// e.g. the rule each unordered update of 2024 day 5 breaks
+    #[arg(long)]
+    pub notes: bool,
+
+    /// Animate the selected days in the terminal instead of solving them
+    ///
+    /// Space pauses, n or the right arrow steps, + and - change the speed and
+    /// q quits. Only days with a `#[visualize(...)]` animation can be selected
+    #[arg(long)]
+    pub visualize: bool,
+
+    /// Frames per second of --visualize
+    #[arg(
+        long,
+        default_value_t = 10,
+        requires = "visualize",
+        value_parser = clap::value_parser!(u32).range(1..=MAX_FPS as i64)
+    )]
+    pub fps: u32,
+
+    /// Write the --visualize frames to PATH instead of the terminal, as an
+    /// animated GIF when PATH ends with `.gif` and as one PNG per frame in the
+    /// PATH directory otherwise
+    #[arg(long, value_name = "PATH", requires = "visualize")]
+    pub export: Option<PathBuf>,
+
+    /// Width and height in pixels of each cell of an --export
+    #[arg(
+        long,
+        value_name = "PIXELS",
+        default_value_t = 4,
+        requires = "export",
+        value_parser = clap::value_parser!(u32).range(1..=64)
+    )]
+    pub scale: u32,
+}
+
+/// Fastest --visualize speed, `+` stops doubling the speed there
+pub const MAX_FPS: u32 = 240;
+
+fn parse_timeout(s: &str) -> Result<Duration, String> {
+    let seconds = s.parse::<f64>().map_err(|e| e.to_string())?;
+    match Duration::try_from_secs_f64(seconds) {
+        Ok(timeout) if !timeout.is_zero() => Ok(timeout),
+        _ => Err("expected a positive number of seconds".to_string()),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Subcommand)]
+pub enum Command {
+    /// Check the answers against the ones recorded in `<year>/answers.toml`
+    Verify,
+    /// Time the parse and solve phases of every selected solution
+    ///
+    /// Solutions run one at a time and without a timeout, --jobs and
+    /// --timeout are ignored
+    Bench(BenchArgs),
+}
+
+#[derive(Debug, Clone, PartialEq, Args)]
+pub struct BenchArgs {
+    /// Number of times each solution is run
+    #[arg(short, long, default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..))]
+    pub runs: u32,
+
+    /// Write the report as JSON, usable as a later --baseline
+    #[arg(long, value_name = "FILE")]
+    pub json: Option<PathBuf>,
+
+    /// Write the report as CSV
+    #[arg(long, value_name = "FILE")]
+    pub csv: Option<PathBuf>,
+
+    /// Compare against a JSON report from an earlier run
+    #[arg(long, value_name = "FILE")]
+    pub baseline: Option<PathBuf>,
+
+    /// Allowed slowdown of the median total time over the baseline, in percent
+    #[arg(
+        long,
+        value_name = "PERCENT",
+        default_value_t = 10.0,
+        requires = "baseline"
+    )]
+    pub threshold: f64,
+}
+
+/// Where a solution reads its input from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    Puzzle,
+    Example(u32),
+    File(PathBuf),
+    Stdin,
+}
+
+impl InputSource {
+    /// Path of the input file for `year` and `day`, `None` when reading from
+    /// stdin
+    pub fn path(&self, root: &Path, year: u32, day: u32) -> Option<PathBuf> {
+        let year_dir = root.join(year.to_string());
+        match self {
+            InputSource::Puzzle => Some(common::input_path(year, day)),
+            InputSource::Example(1) => Some(
+                year_dir
+                    .join("examples")
+                    .join(format!("day{}_example.txt", day)),
+            ),
+            InputSource::Example(k) => Some(
+                year_dir
+                    .join("examples")
+                    .join(format!("day{}_example_{}.txt", day, k)),
+            ),
+            InputSource::File(path) => Some(path.clone()),
+            InputSource::Stdin => None,
+        }
+    }
+}
+
+impl Cli {
+    /// Does `solution` pass the `--year`, `--day` and `--part` filters
+    pub fn selects(&self, solution: &dyn Solution) -> bool {
+        self.selects_day(solution.year(), solution.day())
+            && self.part.is_none_or(|p| p == solution.part())
+    }
+
+    /// Does `year` and `day` pass the `--year` and `--day` filters
+    pub fn selects_day(&self, year: u32, day: u32) -> bool {
+        self.year.is_none_or(|y| y == year) && self.day.is_none_or(|d| d == day)
+    }
+
+    pub fn input_source(&self) -> InputSource {
+        match (&self.input, self.example) {
+            (Some(path), _) if path.as_os_str() == "-" => InputSource::Stdin,
+            (Some(path), _) => InputSource::File(path.clone()),
+            (None, Some(k)) => InputSource::Example(k),
+            (None, None) => InputSource::Puzzle,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::{Answer, AocError, SolutionFn};
+
+    use super::*;
+
+    fn answer(input: &str) -> Result<Answer, AocError> {
+        Ok(input.len().to_string())
+    }
+
+    #[test]
+    fn test_cli_filters() {
+        let cli = Cli::parse_from(["aoc", "--year", "2021", "--part", "2"]);
+        assert!(cli.selects(&SolutionFn::new(2021, 14, 2, answer)));
+        assert!(!cli.selects(&SolutionFn::new(2021, 14, 1, answer)));
+        assert!(!cli.selects(&SolutionFn::new(2022, 1, 2, answer)));
+
+        assert!(Cli::try_parse_from(["aoc", "--day", "26"]).is_err());
+        assert!(Cli::try_parse_from(["aoc", "--part", "3"]).is_err());
+
+        let cli = Cli::parse_from(["aoc", "verify", "--year", "2024"]);
+        assert_eq!(cli.command, Some(Command::Verify));
+        assert_eq!(cli.year, Some(2024));
+
+        let cli = Cli::parse_from(["aoc", "bench", "--day", "6", "--runs", "3"]);
+        let Some(Command::Bench(args)) = cli.command else {
+            panic!("Expected the bench subcommand");
+        };
+        assert_eq!((cli.day, args.runs, args.threshold), (Some(6), 3, 10.0));
+        assert!(Cli::try_parse_from(["aoc", "bench", "--runs", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_pool_options() {
+        let cli = Cli::parse_from(["aoc", "verify", "--jobs", "4", "--timeout", "1.5"]);
+        assert_eq!(cli.jobs, NonZeroUsize::new(4));
+        assert_eq!(cli.timeout, Some(Duration::from_millis(1500)));
+
+        assert!(Cli::try_parse_from(["aoc", "--jobs", "0"]).is_err());
+        assert!(Cli::try_parse_from(["aoc", "--timeout", "0"]).is_err());
+        assert!(Cli::try_parse_from(["aoc", "--timeout", "-1"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_visualize() {
+        let cli = Cli::parse_from(["aoc", "--visualize", "--year", "2021"]);
+        assert!(cli.visualize);
+        assert_eq!(cli.fps, 10);
+        assert!(cli.selects_day(2021, 11) && !cli.selects_day(2024, 6));
+
+        let cli = Cli::parse_from(["aoc", "--visualize", "--fps", "30", "--day", "6"]);
+        assert_eq!((cli.fps, cli.day), (30, Some(6)));
+
+        assert!(Cli::try_parse_from(["aoc", "--fps", "30"]).is_err());
+        assert!(Cli::try_parse_from(["aoc", "--visualize", "--fps", "0"]).is_err());
+        assert!(Cli::try_parse_from(["aoc", "--visualize", "--fps", "1000"]).is_err());
+
+        let cli = Cli::parse_from(["aoc", "--visualize", "--export", "a.gif", "--scale", "2"]);
+        assert_eq!(cli.export, Some(PathBuf::from("a.gif")));
+        assert_eq!(cli.scale, 2);
+        assert!(Cli::try_parse_from(["aoc", "--export", "a.gif"]).is_err());
+        assert!(Cli::try_parse_from(["aoc", "--visualize", "--scale", "2"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_input_source() {
+        let root = Path::new("aoc");
+
+        let cli = Cli::parse_from(["aoc"]);
+        assert_eq!(cli.input_source(), InputSource::Puzzle);
+        assert_eq!(
+            cli.input_source().path(root, 2021, 12),
+            Some(common::input_path(2021, 12))
+        );
+
+        let cli = Cli::parse_from(["aoc", "--example"]);
+        assert_eq!(
+            cli.input_source().path(root, 2021, 12),
+            Some(Path::new("aoc/2021/examples/day12_example.txt").to_path_buf())
+        );
+
+        let cli = Cli::parse_from(["aoc", "--example", "3"]);
+        assert_eq!(
+            cli.input_source().path(root, 2021, 12),
+            Some(Path::new("aoc/2021/examples/day12_example_3.txt").to_path_buf())
+        );
+
+        let cli = Cli::parse_from(["aoc", "--input", "-"]);
+        assert_eq!(cli.input_source(), InputSource::Stdin);
+        assert_eq!(cli.input_source().path(root, 2021, 12), None);
+
+        assert!(Cli::try_parse_from(["aoc", "--input", "a.txt", "--example"]).is_err());
+    }
+}
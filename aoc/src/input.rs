@@ -0,0 +1,45 @@
+use std::{
+    io::{self, Read},
+    path::Path,
+};
+
+use common::{AocError, Solution};
+
+use crate::cli::InputSource;
+
+/// Resolves the input of every selected solution from a single [`InputSource`]
+pub struct Inputs<'a> {
+    root: &'a Path,
+    source: InputSource,
+    stdin: Option<String>,
+}
+
+impl<'a> Inputs<'a> {
+    pub fn new(root: &'a Path, source: InputSource) -> io::Result<Self> {
+        // NOTE, stdin can only be consumed once, every selected part shares it
+        let stdin = if source == InputSource::Stdin {
+            let mut buffer = String::new();
+            io::stdin().read_to_string(&mut buffer)?;
+            Some(buffer)
+        } else {
+            None
+        };
+        Ok(Self {
+            root,
+            source,
+            stdin,
+        })
+    }
+
+    pub fn read(&self, solution: &dyn Solution) -> Result<String, AocError> {
+        self.read_day(solution.year(), solution.day())
+    }
+
+    pub fn read_day(&self, year: u32, day: u32) -> Result<String, AocError> {
+        match (&self.stdin, self.source.path(self.root, year, day)) {
+            (Some(input), _) => Ok(input.clone()),
+            (None, Some(path)) => common::read_file(&path),
+            (None, None) => unreachable!("only stdin has no path"),
+        }
+    }
+}
@@ -0,0 +1,127 @@
+mod bench;
+mod cli;
+mod input;
+mod parallel;
+mod verify;
+mod visualize;
+
+use std::{process::ExitCode, sync::LazyLock};
+
+use clap::Parser;
+use cli::{Cli, Command, InputSource};
+use common::{Registry, Solution};
+use input::Inputs;
+use parallel::{Outcome, Pool};
+
+// NOTE, Solutions register themselves through `#[aoc(...)]`, the year crates
+// only need to be linked into the runner
+use aoc_2021 as _;
+use aoc_2022 as _;
+use aoc_2023 as _;
+use aoc_2024 as _;
+
+// NOTE, Solutions are handed to worker threads that may outlive a timed out
+// run, the registry therefore lives for the whole program
+static REGISTRY: LazyLock<Registry> = LazyLock::new(Registry::collect);
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    if cli.visualize {
+        return visualize::visualize(&cli);
+    }
+
+    let selected = REGISTRY
+        .iter()
+        .filter(|s| cli.selects(*s))
+        .collect::<Vec<_>>();
+    if selected.is_empty() {
+        eprintln!("No solutions match the given --year, --day and --part");
+        return ExitCode::FAILURE;
+    }
+
+    // NOTE, Each year reads its puzzle inputs from `<year>/inputs` (or
+    // `$AOC_INPUT_DIR/<year>`) and its stored examples from `<year>/examples`
+    let root = common::workspace_dir();
+    let source = cli.input_source();
+
+    // NOTE, Recorded answers are only valid for the puzzle inputs
+    if cli.command == Some(Command::Verify) && source != InputSource::Puzzle {
+        eprintln!("verify only checks the puzzle inputs, drop --input and --example");
+        return ExitCode::FAILURE;
+    }
+
+    let inputs = match Inputs::new(root, source) {
+        Ok(inputs) => inputs,
+        Err(e) => {
+            eprintln!("Failed to read the input from stdin: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    // NOTE, Variants are verified too, which checks that they agree with the
+    // default implementation on the real inputs, and benched to compare them
+    let implementations = selected
+        .iter()
+        .flat_map(|s| REGISTRY.implementations(s.year(), s.day(), s.part()))
+        .collect::<Vec<_>>();
+
+    let pool = Pool::new(cli.jobs, cli.timeout);
+    match &cli.command {
+        Some(Command::Verify) => verify::verify(root, &pool, &inputs, &implementations),
+        Some(Command::Bench(args)) => bench::bench(args, &inputs, &implementations),
+        None => run(&pool, &inputs, &selected, cli.notes),
+    }
+}
+
+/// The part, followed by the variant's name for alternative implementations
+fn part_label(solution: &dyn Solution) -> String {
+    match solution.variant() {
+        Some(variant) => format!("{} ({})", solution.part(), variant),
+        None => solution.part().to_string(),
+    }
+}
+
+fn run(
+    pool: &Pool,
+    inputs: &Inputs,
+    solutions: &[&'static dyn Solution],
+    show_notes: bool,
+) -> ExitCode {
+    let mut exit_code = ExitCode::SUCCESS;
+    let mut current_year = None;
+    pool.solve_all(
+        solutions,
+        |solution| inputs.read(solution),
+        |solution, outcome, notes| {
+            if current_year != Some(solution.year()) {
+                current_year = Some(solution.year());
+                println!("Advent of Code {}", solution.year());
+            }
+
+            let (day, part) = (solution.day(), solution.part());
+            if show_notes {
+                for note in notes {
+                    println!("Day {} \tPart {} \t-> Note: {}", day, part, note);
+                }
+            }
+            match outcome {
+                Outcome::Solved(answer) => {
+                    println!("Day {} \tPart {} \t-> Solution: {}", day, part, answer);
+                    return;
+                }
+                Outcome::Panicked(message) => {
+                    eprintln!("Day {} \tPart {} \t-> Panicked: {}", day, part, message)
+                }
+                Outcome::TimedOut(timeout) => {
+                    eprintln!(
+                        "Day {} \tPart {} \t-> Timed out after {:?}",
+                        day, part, timeout
+                    )
+                }
+                Outcome::Failed(e) => eprintln!("Day {} \tPart {} \t-> Error: {}", day, part, e),
+            }
+            exit_code = ExitCode::FAILURE;
+        },
+    );
+    exit_code
+}
@@ -0,0 +1,297 @@
+use std::{
+    any::Any,
+    cell::RefCell,
+    collections::BTreeMap,
+    num::NonZeroUsize,
+    panic::{self, AssertUnwindSafe},
+    sync::{mpsc, Arc, Mutex, Once},
+    thread,
+    time::Duration,
+};
+
+use common::{diagnostics, AocError, Solution};
+
+/// Name of the threads that run a single solution, used by the panic hook to
+/// keep their panics out of the runner output
+const SOLUTION_THREAD: &str = "aoc-solution";
+
+thread_local! {
+    static LAST_PANIC: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// What became of a single solution
+#[derive(Debug)]
+pub enum Outcome {
+    Solved(String),
+    /// The input could not be read or the solution rejected it
+    Failed(AocError),
+    Panicked(String),
+    TimedOut(Duration),
+}
+
+/// Solves many solutions on a fixed number of worker threads
+///
+/// Every solution runs on its own thread so that a panic only takes down that
+/// solution, and a solution that exceeds the timeout is abandoned (it keeps
+/// running in the background until the runner exits)
+#[derive(Debug, Clone, Copy)]
+pub struct Pool {
+    jobs: NonZeroUsize,
+    timeout: Option<Duration>,
+}
+
+impl Pool {
+    pub fn new(jobs: Option<NonZeroUsize>, timeout: Option<Duration>) -> Self {
+        let jobs = jobs
+            .or_else(|| thread::available_parallelism().ok())
+            .unwrap_or(NonZeroUsize::MIN);
+        Self { jobs, timeout }
+    }
+
+    /// Calls `report` once per solution with its outcome and the notes it
+    /// left, in the order of `solutions`, as soon as every solution before it
+    /// has finished
+    pub fn solve_all(
+        &self,
+        solutions: &[&'static dyn Solution],
+        read_input: impl Fn(&dyn Solution) -> Result<String, AocError>,
+        mut report: impl FnMut(&'static dyn Solution, Outcome, Vec<String>),
+    ) {
+        install_panic_hook();
+
+        let (job_tx, job_rx) = mpsc::channel::<(usize, String)>();
+        let (result_tx, result_rx) = mpsc::channel::<(usize, (Outcome, Vec<String>))>();
+        let mut finished = BTreeMap::new();
+        for (index, solution) in solutions.iter().enumerate() {
+            match read_input(*solution) {
+                Ok(input) => job_tx.send((index, input)).unwrap(),
+                Err(e) => {
+                    finished.insert(index, (Outcome::Failed(e), Vec::new()));
+                }
+            }
+        }
+        drop(job_tx);
+
+        let job_rx = Arc::new(Mutex::new(job_rx));
+        let workers = (0..self.jobs.get())
+            .map(|_| {
+                let job_rx = Arc::clone(&job_rx);
+                let result_tx = result_tx.clone();
+                let solutions = solutions.to_vec();
+                let timeout = self.timeout;
+                thread::spawn(move || loop {
+                    // NOTE, The lock is released before the solution runs
+                    let job = job_rx.lock().unwrap().recv();
+                    let Ok((index, input)) = job else {
+                        break;
+                    };
+                    let solved = solve_isolated(solutions[index], input, timeout);
+                    if result_tx.send((index, solved)).is_err() {
+                        break;
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        drop(result_tx);
+
+        // NOTE, Results arrive in completion order, they are held back until
+        // every earlier solution has been reported
+        let mut next = 0;
+        let mut flush = |finished: &mut BTreeMap<usize, (Outcome, Vec<String>)>| {
+            while let Some((outcome, notes)) = finished.remove(&next) {
+                report(solutions[next], outcome, notes);
+                next += 1;
+            }
+        };
+        flush(&mut finished);
+        for (index, solved) in result_rx {
+            finished.insert(index, solved);
+            flush(&mut finished);
+        }
+
+        for worker in workers {
+            worker.join().unwrap();
+        }
+    }
+}
+
+/// Runs `f` on a solution thread of its own and waits for it, a panic is
+/// returned as its message instead of unwinding into the caller
+///
+/// For work that isn't a plain [`Solution::solve`], e.g. timing many runs
+pub fn run_isolated<T: Send>(f: impl FnOnce() -> T + Send) -> Result<T, String> {
+    install_panic_hook();
+    thread::scope(|scope| {
+        thread::Builder::new()
+            .name(SOLUTION_THREAD.to_string())
+            .spawn_scoped(scope, || catch_panic(f))
+            .expect("Failed to spawn a solution thread")
+            .join()
+            .unwrap_or_else(|_| Err("solution thread exited without an answer".to_string()))
+    })
+}
+
+/// Must run on a solution thread for the message to include the location
+fn catch_panic<T>(f: impl FnOnce() -> T) -> Result<T, String> {
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| {
+        LAST_PANIC
+            .take()
+            .unwrap_or_else(|| panic_message(payload.as_ref()).to_string())
+    })
+}
+
+fn solve_isolated(
+    solution: &'static dyn Solution,
+    input: String,
+    timeout: Option<Duration>,
+) -> (Outcome, Vec<String>) {
+    let (tx, rx) = mpsc::channel();
+    thread::Builder::new()
+        .name(SOLUTION_THREAD.to_string())
+        .spawn(move || {
+            let outcome = match catch_panic(|| solution.solve(&input)) {
+                Ok(Ok(answer)) => Outcome::Solved(answer),
+                Ok(Err(e)) => Outcome::Failed(e),
+                Err(message) => Outcome::Panicked(message),
+            };
+            // NOTE, Nobody is listening anymore if the solution timed out
+            let _ = tx.send((outcome, diagnostics::take_notes()));
+        })
+        .expect("Failed to spawn a solution thread");
+
+    let exited = || Outcome::Panicked("solution thread exited without an answer".to_string());
+    match timeout {
+        Some(timeout) => match rx.recv_timeout(timeout) {
+            Ok(solved) => solved,
+            Err(mpsc::RecvTimeoutError::Timeout) => (Outcome::TimedOut(timeout), Vec::new()),
+            Err(mpsc::RecvTimeoutError::Disconnected) => (exited(), Vec::new()),
+        },
+        None => rx.recv().unwrap_or_else(|_| (exited(), Vec::new())),
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "Box<dyn Any>"
+    }
+}
+
+/// Panics on solution threads are recorded for their [`Outcome`] instead of
+/// being printed, panics anywhere else keep the default behaviour
+fn install_panic_hook() {
+    static HOOK: Once = Once::new();
+    HOOK.call_once(|| {
+        let default_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if thread::current().name() == Some(SOLUTION_THREAD) {
+                let message = match info.location() {
+                    Some(location) => format!("{} ({})", panic_message(info.payload()), location),
+                    None => panic_message(info.payload()).to_string(),
+                };
+                LAST_PANIC.set(Some(message));
+            } else {
+                default_hook(info);
+            }
+        }));
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use common::{Answer, SolutionFn, Span};
+
+    use super::*;
+
+    fn sleep_then_answer(input: &str) -> Result<Answer, AocError> {
+        let millis = Span::new(input).parse::<u64>()?;
+        thread::sleep(Duration::from_millis(millis));
+        Ok(input.to_string())
+    }
+
+    fn noted_answer(input: &str) -> Result<Answer, AocError> {
+        diagnostics::note(format!("{} bytes", input.len()));
+        Ok(input.to_string())
+    }
+
+    fn broken_parser(input: &str) -> Result<Answer, AocError> {
+        let numbers = input
+            .split(',')
+            .map(|x| x.parse::<u32>().unwrap())
+            .collect::<Vec<u32>>();
+        Ok(numbers.len().to_string())
+    }
+
+    static SLOW: SolutionFn = SolutionFn::new(2021, 1, 1, sleep_then_answer);
+    static FAST: SolutionFn = SolutionFn::new(2021, 1, 2, sleep_then_answer);
+    static BROKEN: SolutionFn = SolutionFn::new(2021, 2, 1, broken_parser);
+    static HANGS: SolutionFn = SolutionFn::new(2021, 2, 2, sleep_then_answer);
+    static MISSING: SolutionFn = SolutionFn::new(2021, 3, 1, sleep_then_answer);
+    static REJECTED: SolutionFn = SolutionFn::new(2021, 3, 2, sleep_then_answer);
+    static NOTED: SolutionFn = SolutionFn::new(2021, 4, 1, noted_answer);
+
+    #[test]
+    fn test_pool_reports_in_order() {
+        let solutions: Vec<&'static dyn Solution> =
+            vec![&SLOW, &FAST, &BROKEN, &HANGS, &MISSING, &REJECTED, &NOTED];
+        let read_input = |s: &dyn Solution| match (s.day(), s.part()) {
+            (1, 1) => Ok("50".to_string()),
+            (1, 2) => Ok("0".to_string()),
+            (2, 1) => Ok("1,x,3".to_string()),
+            (2, 2) => Ok("5000".to_string()),
+            (3, 1) => Err(AocError::MissingInput(PathBuf::from("day3_input.txt"))),
+            (4, 1) => Ok("note".to_string()),
+            _ => Ok("soon".to_string()),
+        };
+
+        let mut reported = Vec::new();
+        let pool = Pool::new(NonZeroUsize::new(4), Some(Duration::from_millis(500)));
+        let mut notes = Vec::new();
+        pool.solve_all(&solutions, read_input, |s, outcome, n| {
+            reported.push(((s.day(), s.part()), outcome));
+            notes.extend(n);
+        });
+
+        let keys = reported.iter().map(|(k, _)| *k).collect::<Vec<_>>();
+        assert_eq!(
+            keys,
+            vec![(1, 1), (1, 2), (2, 1), (2, 2), (3, 1), (3, 2), (4, 1)]
+        );
+        assert!(matches!(&reported[0].1, Outcome::Solved(answer) if answer == "50"));
+        assert!(matches!(&reported[1].1, Outcome::Solved(answer) if answer == "0"));
+        match &reported[2].1 {
+            Outcome::Panicked(message) => {
+                assert!(message.contains("InvalidDigit"));
+                assert!(message.contains("parallel.rs"));
+            }
+            outcome => panic!("Expected a panic, got {:?}", outcome),
+        }
+        assert!(matches!(
+            reported[3].1,
+            Outcome::TimedOut(timeout) if timeout == Duration::from_millis(500)
+        ));
+        assert!(matches!(
+            &reported[4].1,
+            Outcome::Failed(AocError::MissingInput(path)) if path.ends_with("day3_input.txt")
+        ));
+        assert!(matches!(
+            &reported[5].1,
+            Outcome::Failed(AocError::Parse(_))
+        ));
+        assert_eq!(notes, ["4 bytes"]);
+    }
+
+    #[test]
+    fn test_run_isolated() {
+        let input = "1,x,3";
+        assert_eq!(run_isolated(|| input.len()), Ok(5));
+        let message = run_isolated(|| broken_parser(input)).unwrap_err();
+        assert!(message.contains("InvalidDigit"));
+        assert!(message.contains("parallel.rs"));
+    }
+}
@@ -0,0 +1,179 @@
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    fs,
+    path::Path,
+    process::ExitCode,
+};
+
+use common::Solution;
+use serde::Deserialize;
+
+use crate::{
+    input::Inputs,
+    parallel::{Outcome, Pool},
+};
+
+/// Answers recorded for a single year in `<year>/answers.toml`
+///
+/// ```toml
+/// [day1]
+/// part1 = "1616"
+/// part2 = "1645"
+/// ```
+#[derive(Debug, Default)]
+pub struct Answers {
+    answers: HashMap<(u32, u32), String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DayAnswers {
+    part1: Option<String>,
+    part2: Option<String>,
+}
+
+impl Answers {
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let days: HashMap<String, DayAnswers> =
+            toml::from_str(content).map_err(|e| e.to_string())?;
+
+        let mut answers = HashMap::new();
+        for (section, day_answers) in days {
+            let day = section
+                .strip_prefix("day")
+                .and_then(|d| d.parse::<u32>().ok())
+                .ok_or_else(|| format!("Invalid section [{}], expected [dayN]", section))?;
+            if let Some(answer) = day_answers.part1 {
+                answers.insert((day, 1), answer);
+            }
+            if let Some(answer) = day_answers.part2 {
+                answers.insert((day, 2), answer);
+            }
+        }
+        Ok(Self { answers })
+    }
+
+    /// A year without an `answers.toml` simply has no recorded answers
+    pub fn load(path: &Path) -> Result<Self, String> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Self::parse(&content).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn get(&self, day: u32, part: u32) -> Option<&str> {
+        self.answers.get(&(day, part)).map(|a| a.as_str())
+    }
+}
+
+/// Runs `solutions` against their puzzle inputs and compares every answer
+/// with the recorded one, variants of a part are held to the same answer
+///
+/// Fails on any mismatch, error, panic or timeout, answers that
+/// were never recorded are only reported
+pub fn verify(
+    root: &Path,
+    pool: &Pool,
+    inputs: &Inputs,
+    solutions: &[&'static dyn Solution],
+) -> ExitCode {
+    let mut answers: HashMap<u32, Answers> = HashMap::new();
+    for solution in solutions {
+        if let Entry::Vacant(entry) = answers.entry(solution.year()) {
+            let path = root.join(entry.key().to_string()).join("answers.toml");
+            match Answers::load(&path) {
+                Ok(year_answers) => entry.insert(year_answers),
+                Err(e) => {
+                    eprintln!("Failed to load the recorded answers: {}", e);
+                    return ExitCode::FAILURE;
+                }
+            };
+        }
+    }
+
+    let (mut passed, mut failed, mut unrecorded) = (0, 0, 0);
+    println!("Year \tDay \tPart \tResult");
+    pool.solve_all(
+        solutions,
+        |solution| inputs.read(solution),
+        |solution, outcome, _notes| {
+            let expected = answers[&solution.year()].get(solution.day(), solution.part());
+            let result = match (expected, outcome) {
+                (None, _) => {
+                    unrecorded += 1;
+                    "SKIP \tno recorded answer".to_string()
+                }
+                (Some(expected), Outcome::Solved(actual)) if actual == expected => {
+                    passed += 1;
+                    "PASS".to_string()
+                }
+                (Some(expected), outcome) => {
+                    failed += 1;
+                    match outcome {
+                        Outcome::Solved(actual) => {
+                            format!("FAIL \texpected {:?}, got {:?}", expected, actual)
+                        }
+                        Outcome::Panicked(message) => format!("FAIL \tpanicked: {}", message),
+                        Outcome::TimedOut(timeout) => {
+                            format!("FAIL \ttimed out after {:?}", timeout)
+                        }
+                        Outcome::Failed(e) => format!("FAIL \t{}", e),
+                    }
+                }
+            };
+            println!(
+                "{} \t{} \t{} \t{}",
+                solution.year(),
+                solution.day(),
+                crate::part_label(solution),
+                result
+            );
+        },
+    );
+
+    println!(
+        "{} passed, {} failed, {} without a recorded answer",
+        passed, failed, unrecorded
+    );
+    if failed > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ANSWERS_STR: &str = "
+[day1]
+part1 = \"1616\"
+part2 = \"1645\"
+
+[day13]
+part1 = \"781\"
+";
+
+    #[test]
+    fn test_answers_parse() {
+        let answers = Answers::parse(ANSWERS_STR).unwrap();
+        assert_eq!(answers.get(1, 1), Some("1616"));
+        assert_eq!(answers.get(1, 2), Some("1645"));
+        assert_eq!(answers.get(13, 1), Some("781"));
+        assert_eq!(answers.get(13, 2), None);
+
+        assert!(Answers::parse("[dayone]\npart1 = \"1\"").is_err());
+        assert!(Answers::parse("[day1]\npart3 = \"1\"").is_err());
+    }
+
+    #[test]
+    fn test_recorded_answers_are_valid() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+        for year in ["2021", "2022", "2023", "2024"] {
+            let answers = Answers::load(&root.join(year).join("answers.toml")).unwrap();
+            assert!(answers.get(1, 1).is_some());
+        }
+    }
+}
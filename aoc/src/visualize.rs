@@ -0,0 +1,418 @@
+use std::{
+    io::{self, IsTerminal, Write},
+    path::Path,
+    process::ExitCode,
+    time::{Duration, Instant},
+};
+
+use common::{
+    image::{self, ExportError, Exporter},
+    visualize::{self, Animation, Cell, Color, Frame},
+};
+use crossterm::{
+    cursor::{Hide, MoveTo, Show},
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    execute, queue,
+    style::{self, Attribute, Print, SetAttribute, SetForegroundColor},
+    terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+};
+
+use crate::{
+    cli::{Cli, MAX_FPS},
+    input::Inputs,
+};
+
+/// Plays the animation of every selected day, one after the other
+///
+/// Frames are drawn in place when stdout is a terminal and simply printed one
+/// after the other otherwise, e.g. when piped into a file. With `--export`
+/// they are written as images instead
+pub fn visualize(cli: &Cli) -> ExitCode {
+    if cli.command.is_some() {
+        eprintln!("--visualize can't be combined with verify or bench");
+        return ExitCode::FAILURE;
+    }
+
+    let selected = visualize::visualizations()
+        .into_iter()
+        .filter(|v| cli.selects_day(v.year(), v.day()))
+        .collect::<Vec<_>>();
+    if selected.is_empty() {
+        eprintln!("No visualizations match the given --year and --day");
+        return ExitCode::FAILURE;
+    }
+    if cli.export.is_some() && selected.len() > 1 {
+        eprintln!("--export needs --year and --day to select a single visualization");
+        return ExitCode::FAILURE;
+    }
+
+    let inputs = match Inputs::new(common::workspace_dir(), cli.input_source()) {
+        Ok(inputs) => inputs,
+        Err(e) => {
+            eprintln!("Failed to read the input from stdin: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    // NOTE, The terminal is restored when the player is dropped, even on a panic
+    let mut player = if cli.export.is_none() && io::stdout().is_terminal() {
+        match Player::new(cli.fps) {
+            Ok(player) => Some(player),
+            Err(e) => {
+                eprintln!("Failed to set up the terminal: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+    } else {
+        None
+    };
+
+    for visualization in selected {
+        let title = format!(
+            "Advent of Code {} Day {}",
+            visualization.year(),
+            visualization.day()
+        );
+        let animation = inputs
+            .read_day(visualization.year(), visualization.day())
+            .and_then(|input| visualization.build(&input));
+        let animation = match animation {
+            Ok(animation) => animation,
+            Err(e) => {
+                drop(player);
+                eprintln!("{}: {}", title, e);
+                return ExitCode::FAILURE;
+            }
+        };
+
+        if let Some(path) = &cli.export {
+            let delay = Duration::from_secs(1) / cli.fps;
+            return match export(path, animation, cli.scale as usize, delay) {
+                Ok(frames) => {
+                    println!("{}: wrote {} frames to {}", title, frames, path.display());
+                    ExitCode::SUCCESS
+                }
+                Err(e) => {
+                    eprintln!("{}: {}", title, e);
+                    ExitCode::FAILURE
+                }
+            };
+        }
+
+        let played = match &mut player {
+            Some(player) => player.play(&title, animation),
+            None => print_frames(&mut io::stdout().lock(), &title, animation).map(|_| true),
+        };
+        match played {
+            Ok(true) => {}
+            Ok(false) => break,
+            Err(e) => {
+                drop(player);
+                eprintln!("Failed to draw {}: {}", title, e);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+/// Every frame without colours, each one preceded by its header
+fn print_frames(
+    out: &mut impl Write,
+    title: &str,
+    mut animation: Box<dyn Animation>,
+) -> io::Result<()> {
+    let mut step = 0;
+    loop {
+        writeln!(out, "{}", header(title, step, animation.as_ref()))?;
+        writeln!(out, "{}", animation.frame())?;
+        writeln!(out)?;
+        if !animation.advance() {
+            return Ok(());
+        }
+        step += 1;
+    }
+}
+
+/// Writes every frame as an animated GIF or a directory of PNG files,
+/// depending on the extension of `path`
+fn export(
+    path: &Path,
+    mut animation: Box<dyn Animation>,
+    scale: usize,
+    delay: Duration,
+) -> Result<usize, ExportError> {
+    let exporter = Exporter::new(image::cell_color).scale(scale).delay(delay);
+    let mut started = false;
+    let frames = std::iter::from_fn(|| {
+        if started && !animation.advance() {
+            return None;
+        }
+        started = true;
+        Some(animation.frame())
+    });
+    if path
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("gif"))
+    {
+        exporter.save_gif(path, frames)
+    } else {
+        exporter.save_pngs(path, frames)
+    }
+}
+
+fn header(title: &str, step: usize, animation: &dyn Animation) -> String {
+    match animation.status() {
+        status if status.is_empty() => format!("{}, step {}", title, step),
+        status => format!("{}, step {}: {}", title, step, status),
+    }
+}
+
+/// Owns the terminal while animations are drawn in the alternate screen
+struct Player {
+    fps: u32,
+    paused: bool,
+}
+
+enum Input {
+    /// Time for the next frame
+    Tick,
+    Key(KeyEvent),
+    /// Anything that only needs the frame to be drawn again, e.g. a resize
+    Redraw,
+}
+
+impl Player {
+    fn new(fps: u32) -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), EnterAlternateScreen, Hide)?;
+        Ok(Self { fps, paused: false })
+    }
+
+    /// Runs `animation` until it finishes and a key is pressed, `false` when
+    /// the user asked to quit
+    fn play(&mut self, title: &str, mut animation: Box<dyn Animation>) -> io::Result<bool> {
+        let mut out = io::stdout().lock();
+        let mut step = 0;
+        let mut finished = false;
+        let mut next_frame = Instant::now();
+        loop {
+            let frame = animation.frame();
+            let header = header(title, step, animation.as_ref());
+            let state = match (finished, self.paused) {
+                (true, _) => "finished, press any key to continue",
+                (false, true) => "paused",
+                (false, false) => "playing",
+            };
+            let footer = format!(
+                "{} fps, {} | space: pause, n: step, +/-: speed, q: quit",
+                self.fps, state
+            );
+            draw(&mut out, terminal::size()?, &header, &frame, &footer)?;
+
+            let timeout = (!finished && !self.paused)
+                .then(|| next_frame.saturating_duration_since(Instant::now()));
+            let advance = match wait(timeout)? {
+                Input::Tick => true,
+                Input::Redraw => false,
+                Input::Key(key) => {
+                    let quit = matches!(key.code, KeyCode::Char('q') | KeyCode::Esc)
+                        || (key.code == KeyCode::Char('c')
+                            && key.modifiers.contains(KeyModifiers::CONTROL));
+                    if quit {
+                        return Ok(false);
+                    }
+                    if finished {
+                        return Ok(true);
+                    }
+                    match key.code {
+                        KeyCode::Char(' ') => {
+                            self.paused = !self.paused;
+                            false
+                        }
+                        KeyCode::Char('+') | KeyCode::Char('=') => {
+                            self.fps = (self.fps * 2).min(MAX_FPS);
+                            false
+                        }
+                        KeyCode::Char('-') => {
+                            self.fps = (self.fps / 2).max(1);
+                            false
+                        }
+                        // NOTE, Stepping by hand pauses the animation
+                        KeyCode::Char('n') | KeyCode::Right => {
+                            self.paused = true;
+                            true
+                        }
+                        _ => false,
+                    }
+                }
+            };
+
+            if advance {
+                if animation.advance() {
+                    step += 1;
+                } else {
+                    finished = true;
+                }
+                next_frame = Instant::now() + Duration::from_secs(1) / self.fps;
+            }
+        }
+    }
+}
+
+impl Drop for Player {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Waits for a key or until `timeout` has passed, forever without a timeout
+fn wait(timeout: Option<Duration>) -> io::Result<Input> {
+    if let Some(timeout) = timeout {
+        if !event::poll(timeout)? {
+            return Ok(Input::Tick);
+        }
+    }
+    Ok(match event::read()? {
+        Event::Key(key) if key.kind != KeyEventKind::Release => Input::Key(key),
+        _ => Input::Redraw,
+    })
+}
+
+/// Draws the frame between a header and a footer line, cropping whatever
+/// doesn't fit in a `(columns, rows)` terminal
+fn draw(
+    out: &mut impl Write,
+    (columns, rows): (u16, u16),
+    header: &str,
+    frame: &Frame,
+    footer: &str,
+) -> io::Result<()> {
+    let visible_rows = rows.saturating_sub(2);
+    let cropped = frame.width() > columns as usize || frame.height() > visible_rows as usize;
+    let header = if cropped {
+        format!("{} (cropped)", header)
+    } else {
+        header.to_string()
+    };
+    queue!(
+        out,
+        MoveTo(0, 0),
+        Print(crop(&header, columns)),
+        Clear(ClearType::UntilNewLine)
+    )?;
+
+    let mut drawn = 0;
+    for (y, row) in frame.rows().take(visible_rows as usize).enumerate() {
+        queue!(out, MoveTo(0, y as u16 + 1))?;
+        let mut style = None;
+        for cell in row.iter().take(columns as usize) {
+            if style != Some((cell.color, cell.bold)) {
+                style = Some((cell.color, cell.bold));
+                set_style(out, cell)?;
+            }
+            queue!(out, Print(cell.symbol))?;
+        }
+        queue!(
+            out,
+            SetAttribute(Attribute::Reset),
+            Clear(ClearType::UntilNewLine)
+        )?;
+        drawn += 1;
+    }
+
+    queue!(
+        out,
+        MoveTo(0, drawn + 1),
+        Clear(ClearType::FromCursorDown),
+        MoveTo(0, rows.saturating_sub(1)),
+        Print(crop(footer, columns))
+    )?;
+    out.flush()
+}
+
+fn set_style(out: &mut impl Write, cell: &Cell) -> io::Result<()> {
+    queue!(out, SetAttribute(Attribute::Reset))?;
+    if cell.bold {
+        queue!(out, SetAttribute(Attribute::Bold))?;
+    }
+    if let Some(color) = cell.color {
+        let color = match color {
+            Color::Black => style::Color::Black,
+            Color::Red => style::Color::Red,
+            Color::Green => style::Color::Green,
+            Color::Yellow => style::Color::Yellow,
+            Color::Blue => style::Color::Blue,
+            Color::Magenta => style::Color::Magenta,
+            Color::Cyan => style::Color::Cyan,
+            Color::White => style::Color::White,
+            Color::Rgb(r, g, b) => style::Color::Rgb { r, g, b },
+        };
+        queue!(out, SetForegroundColor(color))?;
+    }
+    Ok(())
+}
+
+fn crop(line: &str, columns: u16) -> String {
+    line.chars().take(columns as usize).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use common::{Grid, Point};
+
+    use super::*;
+
+    /// Counts down to 0, showing the number as a row of `#`
+    struct Countdown(usize);
+
+    impl Animation for Countdown {
+        fn advance(&mut self) -> bool {
+            self.0 = self.0.saturating_sub(1);
+            self.0 > 0
+        }
+
+        fn frame(&self) -> Frame {
+            let mut frame = Grid::new(3, 1, Cell::new('.'));
+            for x in 0..self.0 {
+                frame[Point::new(x, 0)] = Cell::colored('#', Color::Green);
+            }
+            frame
+        }
+
+        fn status(&self) -> String {
+            format!("{} left", self.0)
+        }
+    }
+
+    #[test]
+    fn test_print_frames() {
+        let mut out = Vec::new();
+        print_frames(&mut out, "Countdown", Box::new(Countdown(3))).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "Countdown, step 0: 3 left\n###\n\n\
+             Countdown, step 1: 2 left\n##.\n\n\
+             Countdown, step 2: 1 left\n#..\n\n"
+        );
+    }
+
+    #[test]
+    fn test_draw_crops_to_the_terminal() {
+        let frame = Countdown(3).frame();
+        let mut out = Vec::new();
+        draw(&mut out, (2, 3), "Countdown", &frame, "q: quit").unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("Co"));
+        assert!(!out.contains("Cou") && !out.contains("###"));
+        assert_eq!(out.matches('#').count(), 2);
+        assert!(out.contains("\x1b[38;5;10m"));
+
+        let mut out = Vec::new();
+        draw(&mut out, (80, 24), "Countdown", &frame, "q: quit").unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("Countdown") && !out.contains("cropped"));
+        assert!(out.contains("###") && out.contains("q: quit"));
+    }
+}